@@ -1,111 +1,926 @@
-use std::{num::NonZeroU64, str::FromStr};
+mod alphabet;
+mod base;
+mod complex;
+mod constants;
+mod digit_set;
+mod error;
+mod expansion;
+mod expression;
+mod integer;
+mod mixed_radix;
+mod normalize;
+mod output_digits;
+mod rational;
+mod rounding;
 
-use bigdecimal::{BigDecimal, ToPrimitive};
+use std::{borrow::Cow, fmt, num::NonZeroU64, str::FromStr};
+
+use bigdecimal::{num_bigint::BigInt, BigDecimal, ToPrimitive};
+
+pub use alphabet::Alphabet;
+pub use base::Base;
+pub use complex::Complex;
+use complex::{join_parts, split_complex};
+use constants::{constant, is_constant};
+pub use digit_set::DigitSet;
+use digit_set::{val_from_digit_set, val_to_digit_set};
+pub use error::ConversionError;
+use expansion::{finite_expansions, val_to_expansion};
+pub use expression::Expression;
+pub use mixed_radix::MixedRadix;
+pub use normalize::Normalization;
+use normalize::{carry, normalize};
+use num_rational::BigRational;
+pub use output_digits::OutputDigits;
+use rational::{
+    decimal_from_rational, exact_base, exact_val_from_base, rational_to_digits,
+    rational_to_rounded_digits,
+};
+pub use rounding::Rounding;
+use rounding::{certify, val_to_rounded_digits, Certainty, Certified};
+
+/// What a conversion writes, so that it only has to be worked out once.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Output {
+    /// The output written with the output alphabet
+    pub string: String,
+    /// The output written with the usual numerals whatever the output
+    /// alphabet, so that its digits can be read back one by one
+    pub numerals: String,
+    /// A bound on how far the value of the output can be from the value of
+    /// the input, rounded up to two significant digits, when it's tracked
+    pub error_bound: Option<BigDecimal>,
+}
+
+/// The finite representations of a value that a search found.
+#[derive(PartialEq, Clone, Debug)]
+pub struct FiniteExpansions {
+    /// The representations, from the usual one down
+    pub expansions: Vec<String>,
+    /// Whether the search stopped before trying everything, so there may be
+    /// more representations
+    pub is_truncated: bool,
+}
 
 #[derive(PartialEq, Clone)]
 pub struct BaseConversion {
     pub input_string: String,
-    pub input_base: BigDecimal,
+    pub input_base: Base,
     pub input_base_string: String,
-    pub output_base: BigDecimal,
+    pub input_digit_set: DigitSet,
+    /// The symbols the input digits are written with
+    pub input_alphabet: Alphabet,
+    pub input_digit_set_string: String,
+    pub output_base: Base,
     pub output_base_string: String,
+    pub output_digit_set: DigitSet,
+    /// The symbols the output digits are written with
+    pub output_alphabet: Alphabet,
+    pub output_digit_set_string: String,
+    /// How many fractional digits to write in the output base
+    pub precision: usize,
+    /// How the last output digit is chosen
+    pub rounding: Rounding,
+    /// Whether input digits past the end of the base are carried instead of
+    /// rejected, like `[12]` in base 10
+    pub permissive: bool,
+    pub exponent_base_string: String,
+    /// The base to write exponents in, when the output is written in
+    /// scientific notation
+    pub exponent_base: Option<Base>,
+    /// Why the input base or digit set couldn't be read, if it couldn't
+    pub input_settings_error: Option<ConversionError>,
+    /// Why the output base, digit set or exponent base couldn't be read, if
+    /// it couldn't
+    pub output_settings_error: Option<ConversionError>,
 }
 
 static FALLBACK_INPUT_BASE: i32 = 10;
 static FALLBACK_OUTPUT_BASE: i32 = 10;
+/// The most fractional digits that can be asked for, since every digit takes
+/// more work than the one before it.
+pub static MAX_PRECISION: usize = 1000;
+/// How many more digits the accurate output has than the usual output.
+static ACCURATE_EXTRA_DIGITS: usize = 40;
+/// The fewest decimal places that values are worked out to.
+static MIN_WORKING_PLACES: i64 = 50;
+/// Extra decimal places to absorb the rounding of intermediate steps.
+static GUARD_PLACES: i64 = 20;
+/// How many more times the output digits can be worked out to make sure of
+/// them, each time with twice as many extra places as the last.
+static MAX_EXTRA_ATTEMPTS: u32 = 3;
+/// The most decimal digits a positional base can have before the radix point,
+/// so that every base is less than `1e100`.
+static MAX_BASE_DIGITS: i64 = 100;
+/// The largest exponent an input value can be written with, like the `30` of
+/// `1e-30`.
+static MAX_EXPONENT: isize = 1000;
 
 impl BaseConversion {
+    #[allow(clippy::too_many_arguments)]
     pub fn new_with_defaults(
         input_string: String,
         input_base_string: String,
+        input_digit_set_string: String,
         output_base_string: String,
+        output_digit_set_string: String,
+        precision: usize,
+        exponent_base_string: String,
+        permissive: bool,
+        rounding: Rounding,
         base_conversion: Option<&Self>,
     ) -> Self {
+        let precision = precision.min(MAX_PRECISION);
+        // A setting that can't be read keeps its last value, or the default,
+        // and the first such error is kept to report in its place
+        let mut input_settings_error = None;
+        let mut output_settings_error = None;
+        let keep = |error: &mut Option<ConversionError>, e| {
+            error.get_or_insert(e);
+        };
+        // Scientific notation is off until an exponent base is given
+        let exponent_base = match exponent_base_string.trim() {
+            "" => None,
+            s => Self::parse_base(s, precision)
+                .map(Some)
+                .unwrap_or_else(|e| {
+                    keep(&mut output_settings_error, e);
+                    base_conversion.and_then(|c| c.exponent_base.clone())
+                }),
+        };
+        let output_base = Self::parse_base(&output_base_string, precision).unwrap_or_else(|e| {
+            keep(&mut output_settings_error, e);
+            base_conversion.map_or_else(
+                || Base::from(BigDecimal::from(FALLBACK_OUTPUT_BASE)),
+                |c| c.output_base.clone(),
+            )
+        });
+        // Every digit of the input can scale up the error in its base
+        let input_places =
+            Self::places_for(&output_base, precision) + input_string.chars().count() as i64;
+        let (input_digit_set, input_alphabet) =
+            DigitSet::parse_with_alphabet(&input_digit_set_string).unwrap_or_else(|e| {
+                keep(&mut input_settings_error, e);
+                base_conversion.map_or((DigitSet::Standard, Alphabet::Numerals), |c| {
+                    (c.input_digit_set.clone(), c.input_alphabet.clone())
+                })
+            });
+        let (output_digit_set, output_alphabet) =
+            DigitSet::parse_with_alphabet(&output_digit_set_string).unwrap_or_else(|e| {
+                keep(&mut output_settings_error, e);
+                base_conversion.map_or((DigitSet::Standard, Alphabet::Numerals), |c| {
+                    (c.output_digit_set.clone(), c.output_alphabet.clone())
+                })
+            });
+        let input_base = Base::parse(&input_base_string, input_places).unwrap_or_else(|e| {
+            keep(&mut input_settings_error, e);
+            base_conversion.map_or_else(
+                || Base::from(BigDecimal::from(FALLBACK_INPUT_BASE)),
+                |c| c.input_base.clone(),
+            )
+        });
         Self {
+            input_base,
             input_string,
-            input_base: BigDecimal::from_str(&input_base_string)
-                .or_else(|e| val_from_popular_strings(&input_base_string).ok_or(e))
-                .or_else(|e| base_conversion.map(|c| c.input_base.clone()).ok_or(e))
-                .unwrap_or_else(|_| BigDecimal::from(FALLBACK_INPUT_BASE)),
             input_base_string,
-            output_base: BigDecimal::from_str(&output_base_string)
-                .or_else(|e| val_from_popular_strings(&output_base_string).ok_or(e))
-                .or_else(|e| base_conversion.map(|c| c.output_base.clone()).ok_or(e))
-                .unwrap_or_else(|_| BigDecimal::from(FALLBACK_OUTPUT_BASE)),
+            input_digit_set,
+            input_alphabet,
+            input_digit_set_string,
+            output_base,
             output_base_string,
+            output_digit_set,
+            output_alphabet,
+            output_digit_set_string,
+            precision,
+            rounding,
+            permissive,
+            exponent_base_string,
+            exponent_base,
+            input_settings_error,
+            output_settings_error,
+        }
+    }
+
+    /// The fractional output digits get less significant in bigger bases, so
+    /// values are worked out to enough decimal places for the last digit of
+    /// the accurate output.
+    fn places_for(base: &Base, precision: usize) -> i64 {
+        let precision = -((precision + ACCURATE_EXTRA_DIGITS) as isize) - 1;
+        match base {
+            Base::Positional(base) => working_places(precision, &digit_bound(base)),
+            Base::MixedRadix(_) | Base::Zeckendorf => {
+                working_places(precision, &BigDecimal::from(10))
+            }
+        }
+    }
+
+    /// Parse a base, evaluating any expression to enough decimal places for
+    /// the precision.
+    fn parse_base(base_string: &str, precision: usize) -> Result<Base, ConversionError> {
+        let rough = Base::from_str(base_string)?;
+        Base::parse(base_string, Self::places_for(&rough, precision))
+    }
+
+    fn working_places(&self) -> i64 {
+        Self::places_for(&self.output_base, self.precision)
+    }
+
+    pub fn base_10_value(&self) -> Result<Complex, ConversionError> {
+        self.value_to_places(self.working_places())
+    }
+
+    fn value_to_places(&self, places: i64) -> Result<Complex, ConversionError> {
+        if let Some(error) = &self.input_settings_error {
+            return Err(error.clone());
+        }
+        match self.exact_value() {
+            Some(v) => Ok(Complex::from(decimal_from_rational(&v, places))),
+            None => val_from_base_system(
+                &self.input_numerals(),
+                &self.input_base,
+                &self.input_digit_set,
+                places,
+            ),
+        }
+        .map(|v| v.round(places).normalized())
+        .map_err(|e| self.locate_in_input(e))
+    }
+
+    /// Fill in where an error is in the input, so it can be pointed out.
+    fn locate_in_input(&self, error: ConversionError) -> ConversionError {
+        match &self.input_base {
+            Base::Positional(base) => {
+                error.locate(&self.input_string, &self.input_alphabet, &digit_bound(base))
+            }
+            Base::MixedRadix(_) | Base::Zeckendorf => error.locate(
+                &self.input_string,
+                &Alphabet::Numerals,
+                &BigDecimal::from(10),
+            ),
+        }
+    }
+
+    /// An error when the output can't be written because a base or digit set
+    /// couldn't be read. Input settings are wrapped like other input errors.
+    fn check_settings(&self) -> Result<(), ConversionError> {
+        match (&self.output_settings_error, &self.input_settings_error) {
+            (Some(error), _) => Err(error.clone()),
+            (None, Some(error)) => Err(ConversionError::InvalidInput(Box::new(error.clone()))),
+            (None, None) => Ok(()),
+        }
+    }
+
+    /// The value worked out to enough extra decimal places that a tiny value
+    /// has as many accurate significant digits as a value near 1.
+    fn significant_value(&self) -> Result<Complex, ConversionError> {
+        let places = self.working_places();
+        let value = self.value_to_places(places)?;
+        let leading_zeros = match value.norm_sqr() == bigdecimal::Zero::zero() {
+            // Enough places for the smallest value the input could be written
+            // with, in case it's only rounded to zero
+            true => {
+                let digit_places = match &self.input_base {
+                    Base::Positional(base) => magnitude(&digit_bound(base)).max(1),
+                    Base::MixedRadix(_) | Base::Zeckendorf => 1,
+                };
+                (MAX_EXPONENT as i64 + self.input_string.chars().count() as i64) * digit_places
+            }
+            false => -magnitude(&value.re.abs().max(value.im.abs())),
+        };
+        match leading_zeros > 0 {
+            true => self.value_to_places(places + leading_zeros),
+            false => Ok(value),
+        }
+    }
+
+    /// The exact value of a base, when it's rational and written with the
+    /// standard digits.
+    fn exact_base(base_string: &str, digit_set: &DigitSet) -> Option<BigRational> {
+        match (Base::from_str(base_string), digit_set) {
+            (Ok(Base::Positional(_)), DigitSet::Standard) => exact_base(base_string),
+            _ => None,
+        }
+    }
+
+    /// The exact value of the input, when it's a real value written in a
+    /// rational base.
+    fn exact_value(&self) -> Option<BigRational> {
+        Self::exact_base(&self.input_base_string, &self.input_digit_set)
+            .and_then(|base| exact_val_from_base(&self.input_numerals(), &base))
+    }
+
+    /// The input rewritten with the usual numerals, since alphabets only apply
+    /// to positional bases, and carried in permissive mode.
+    fn input_numerals(&self) -> String {
+        match &self.input_base {
+            Base::Positional(base) => {
+                let numerals = self
+                    .input_alphabet
+                    .to_numerals(&self.input_string, &digit_bound(base));
+                self.carried(&numerals, base).unwrap_or(numerals)
+            }
+            Base::MixedRadix(_) | Base::Zeckendorf => self.input_string.clone(),
+        }
+    }
+
+    /// In permissive mode, the input with any digits past the end of the base
+    /// carried, when there are any and the base is an integer base with the
+    /// standard digits.
+    fn carried(&self, numerals: &str, base: &Complex) -> Option<String> {
+        if !self.permissive || self.input_digit_set != DigitSet::Standard {
+            return None;
+        }
+        let (mantissa, _) =
+            split_exponent(numerals, digit_bound(base) > BigDecimal::from(14)).ok()?;
+        let exponent = &numerals[mantissa.len()..];
+        carry(mantissa, base)
+            .ok()
+            .flatten()
+            .map(|carried| carried + exponent)
+    }
+
+    /// A warning when the input was only readable because permissive mode
+    /// carried some of its digits, showing the carried input.
+    pub fn input_warning(&self) -> Option<String> {
+        if self.input_settings_error.is_some() {
+            return None;
+        }
+        let Base::Positional(base) = &self.input_base else {
+            return None;
+        };
+        let bound = digit_bound(base);
+        let numerals = self.input_alphabet.to_numerals(&self.input_string, &bound);
+        self.carried(&numerals, base).map(|carried| {
+            format!(
+                "Digits past the end of the base were carried: {}",
+                self.input_alphabet.to_symbols(&carried, &bound)
+            )
+        })
+    }
+
+    /// Rewrite output in the usual numerals with the output alphabet.
+    fn output_symbols(&self, numerals: String) -> String {
+        match &self.output_base {
+            Base::Positional(base) => self
+                .output_alphabet
+                .to_symbols(&numerals, &digit_bound(base)),
+            Base::MixedRadix(_) | Base::Zeckendorf => numerals,
+        }
+    }
+
+    /// Exact values in rational output bases are only rounded when they're
+    /// written. In positive integer bases they're written in full, with any
+    /// repeating digits in parentheses, like `0.(142857)`. Other values are cut
+    /// off after `-precision - 1` fractional digits.
+    ///
+    /// When the input can't be read, the error is wrapped in
+    /// `ConversionError::InvalidInput`, since it's about the input rather than
+    /// the output.
+    fn output_with_precision(&self, precision: isize) -> Result<String, ConversionError> {
+        self.output_with_bound(precision).map(|(output, _)| output)
+    }
+
+    /// The output written with the usual numerals, along with a bound on its
+    /// error. Only outputs in real bases greater than 1 with the standard
+    /// digits have their error tracked.
+    fn output_with_bound(
+        &self,
+        precision: isize,
+    ) -> Result<(String, Option<BigDecimal>), ConversionError> {
+        self.check_settings()?;
+        if let Some(exponent_base) = &self.exponent_base {
+            return self
+                .significant_value()
+                .map_err(|e| ConversionError::InvalidInput(Box::new(e)))
+                .and_then(|v| {
+                    val_to_scientific(
+                        &v,
+                        &self.output_base,
+                        &self.output_digit_set,
+                        exponent_base,
+                        precision,
+                        self.rounding,
+                        self.working_places(),
+                    )
+                })
+                .map(|output| (output, None));
+        }
+        if let Some((digits, error)) = self.exact_digits(precision) {
+            let bound = error.map(|e| decimal_from_rational(&e, self.working_places()));
+            return Ok((digits.to_string(), bound));
+        }
+        match self.certain_digits(precision)? {
+            Some(certified) => Ok((certified.digits.to_string(), Some(certified.error))),
+            None => self
+                .base_10_value()
+                .map_err(|e| ConversionError::InvalidInput(Box::new(e)))
+                .and_then(|v| {
+                    val_to_base_system(
+                        &v,
+                        &self.output_base,
+                        &self.output_digit_set,
+                        precision,
+                        self.rounding,
+                    )
+                })
+                .map(|output| (output, None)),
+        }
+    }
+
+    /// The output digits worked out exactly, when the value and the output base
+    /// are both rational, along with how far they are from the value when
+    /// that's known.
+    ///
+    /// Digits that run past the precision without repeating are cut off and
+    /// rounded. Rounding is only done exactly in bases greater than 1, so
+    /// other bases only use digits that had to be cut off when they're meant
+    /// to be truncated.
+    fn exact_digits(&self, precision: isize) -> Option<(Digits, Option<BigRational>)> {
+        let (base, value) = Self::exact_base(&self.output_base_string, &self.output_digit_set)
+            .zip(self.exact_value())?;
+        let digits = rational_to_digits(&value, &base, precision)?;
+        let runs_past = digits.repeating_from.is_none()
+            && digits
+                .pairs
+                .last()
+                .is_some_and(|&(_, exp)| exp <= precision);
+        if !digits.is_elided && !runs_past {
+            return Some((digits, Some(bigdecimal::Zero::zero())));
+        }
+        match rational_to_rounded_digits(&value, &base, precision, self.rounding) {
+            Some((digits, error)) => Some((digits, Some(error))),
+            None => (self.rounding == Rounding::Truncate).then_some((digits, None)),
+        }
+    }
+
+    /// The output digits of a real value in a real base greater than 1 with
+    /// the standard digits, along with a bound on their error.
+    ///
+    /// The fixed places that values are usually worked out to can leave a
+    /// digit on the wrong side of a digit boundary, so the value and both
+    /// bases are worked out to more places, adding twice as many guard places
+    /// each time, until every digit is certain. Digits that are still
+    /// uncertain after `MAX_EXTRA_ATTEMPTS` more attempts are written as
+    /// `UNCERTAIN`.
+    fn certain_digits(&self, precision: isize) -> Result<Option<Certified>, ConversionError> {
+        let is_real_base = matches!(
+            &self.output_base,
+            Base::Positional(base) if base.is_real() && base.re > bigdecimal::One::one()
+        );
+        if !is_real_base
+            || self.exponent_base.is_some()
+            || self.output_digit_set != DigitSet::Standard
+        {
+            return Ok(None);
+        }
+        let mut places = self.working_places();
+        let mut conversion = Cow::Borrowed(self);
+        let mut attempt = 0;
+        loop {
+            let value = conversion
+                .value_to_places(places)
+                .map_err(|e| ConversionError::InvalidInput(Box::new(e)))?;
+            let Base::Positional(base) = &conversion.output_base else {
+                return Ok(None);
+            };
+            if !value.is_real() || !base.is_real() {
+                return Ok(None);
+            }
+            let (value_error, base_error) = self.errors_at(places);
+            let certified = certify(
+                &value.re,
+                &value_error,
+                &base.re,
+                &base_error,
+                precision,
+                self.rounding,
+                places,
+            )?;
+            if certified.certainty == Certainty::Certain || attempt == MAX_EXTRA_ATTEMPTS {
+                return Ok(Some(certified));
+            }
+            places += GUARD_PLACES << attempt;
+            attempt += 1;
+            conversion = Cow::Owned(self.at_places(places));
+        }
+    }
+
+    /// Bounds on the errors of the value and the output base when they're
+    /// worked out to the given number of decimal places.
+    ///
+    /// Values are exact to the last place only when they're worked out from a
+    /// rational value, and constants and expressions are only worked out to
+    /// within the guard places.
+    fn errors_at(&self, places: i64) -> (BigDecimal, BigDecimal) {
+        let inexact = BigDecimal::new(1.into(), places - GUARD_PLACES);
+        let value_error = match self.exact_value() {
+            Some(_) => BigDecimal::new(1.into(), places),
+            None => inexact.clone(),
+        };
+        let base_error = match Complex::from_str(&self.output_base_string) {
+            Ok(_) => bigdecimal::Zero::zero(),
+            Err(_) => inexact,
+        };
+        (value_error, base_error)
+    }
+
+    /// The same conversion, with its bases worked out to the given number of
+    /// decimal places. A base that can't be parsed is kept as it was.
+    fn at_places(&self, places: i64) -> Self {
+        let reparse = |base_string: &str, base: &Base, places| {
+            Base::parse(base_string, places).unwrap_or_else(|_| base.clone())
+        };
+        let input_places = places + self.input_string.chars().count() as i64;
+        Self {
+            input_base: reparse(&self.input_base_string, &self.input_base, input_places),
+            output_base: reparse(&self.output_base_string, &self.output_base, places),
+            ..self.clone()
+        }
+    }
+
+    /// The output, along with a bound on how far its value can be from the
+    /// value of the input, all worked out at once.
+    pub fn output(&self) -> Result<Output, ConversionError> {
+        let (numerals, error_bound) = self.output_with_bound(-(self.precision as isize) - 1)?;
+        Ok(Output {
+            string: self.output_symbols(numerals.clone()),
+            numerals,
+            error_bound: error_bound.map(|bound| {
+                bound
+                    .with_precision_round(NonZeroU64::new(2).unwrap(), bigdecimal::RoundingMode::Up)
+            }),
+        })
+    }
+
+    pub fn output_string_accurate(&self) -> Result<String, ConversionError> {
+        self.output_with_precision(-((self.precision + ACCURATE_EXTRA_DIGITS) as isize) - 1)
+            .map(|o| self.output_symbols(o))
+    }
+
+    /// Whether the input digits are the greedy expansion of their value in a
+    /// real input base, along with the carries that rewrite them into it.
+    pub fn input_normalization(&self) -> Result<Normalization, ConversionError> {
+        if let Some(error) = &self.input_settings_error {
+            return Err(error.clone());
+        }
+        match (&self.input_base, &self.input_digit_set) {
+            (Base::Positional(base), DigitSet::Standard) if base.is_real() => {
+                let bound = digit_bound(base);
+                normalize(&self.input_numerals(), &base.re, self.working_places())
+                    .map(|n| Normalization {
+                        steps: n
+                            .steps
+                            .into_iter()
+                            .map(|s| self.input_alphabet.to_symbols(&s, &bound))
+                            .collect(),
+                        ..n
+                    })
+                    .map_err(|e| self.locate_in_input(e))
+            }
+            _ => Err(ConversionError::unsupported(
+                "Only real bases with the standard digits can be normalized",
+            )),
+        }
+    }
+
+    /// Every finite representation of the value in the output base that has
+    /// at most `max_digits` digits from the leading digit of the usual
+    /// representation, from the usual one down, or as many as could be found.
+    /// Only real bases greater than 1 are supported.
+    pub fn finite_expansions(
+        &self,
+        max_digits: usize,
+    ) -> Result<FiniteExpansions, ConversionError> {
+        self.check_settings()?;
+        let base = match &self.output_base {
+            Base::Positional(base) if base.is_real() => base.re.clone(),
+            base => {
+                return Err(ConversionError::unsupported(format!(
+                    "Base {base} has no alternative expansions"
+                )))
+            }
+        };
+        // The last digit can be `max_digits` places below the leading one
+        let places = self.working_places() + max_digits as i64 * magnitude(&base).max(1);
+        let value = self.value_to_places(places)?;
+        if !value.is_real() {
+            return Err(ConversionError::unsupported(
+                "Only real values have alternative expansions",
+            ));
+        }
+        finite_expansions(&value.re, &base, max_digits, places).map(|(expansions, is_truncated)| {
+            FiniteExpansions {
+                expansions: expansions
+                    .iter()
+                    .map(|d| self.output_symbols(d.to_string()))
+                    .collect(),
+                is_truncated,
+            }
+        })
+    }
+
+    /// The digits of the output, one at a time, without a fixed precision. Only
+    /// real values in real bases greater than 1 with the standard digits are
+    /// supported.
+    pub fn output_digits(&self) -> Result<OutputDigits, ConversionError> {
+        self.check_settings()?;
+        OutputDigits::new(self)
+    }
+
+    /// Whether the output is written with positional digits, as opposed to a
+    /// complex value written as separate real and imaginary parts, or a value
+    /// in scientific notation.
+    pub fn output_is_positional(&self) -> bool {
+        if self.exponent_base.is_some() {
+            return false;
+        }
+        match &self.output_base {
+            Base::Positional(base) => {
+                !base.is_real() || self.base_10_value().map(|v| v.is_real()).unwrap_or(true)
+            }
+            Base::MixedRadix(_) | Base::Zeckendorf => true,
+        }
+    }
+}
+
+#[cfg(test)]
+impl BaseConversion {
+    /// A conversion with the standard digits and the default settings, for
+    /// tests to change with struct update syntax.
+    pub fn for_test(input: &str, input_base: &str, output_base: &str) -> Self {
+        Self::new_with_defaults(
+            input.to_string(),
+            input_base.to_string(),
+            String::from("standard"),
+            output_base.to_string(),
+            String::from("standard"),
+            8,
+            String::new(),
+            false,
+            Rounding::Truncate,
+            None,
+        )
+    }
+
+    /// Read the conversion again from its settings, for when a test changed
+    /// the digit sets, the exponent base or the precision.
+    pub fn reparsed(self) -> Self {
+        Self::new_with_defaults(
+            self.input_string,
+            self.input_base_string,
+            self.input_digit_set_string,
+            self.output_base_string,
+            self.output_digit_set_string,
+            self.precision,
+            self.exponent_base_string,
+            self.permissive,
+            self.rounding,
+            None,
+        )
+    }
+}
+
+/// Parse a value in any base, which may be a fraction like `1/7` with both
+/// parts written in that base, and either part may end with an exponent, like
+/// `1.2e-30`. The value is accurate to about `places` decimal places.
+fn val_from_base_system(
+    input: &str,
+    base: &Base,
+    digit_set: &DigitSet,
+    places: i64,
+) -> Result<Complex, ConversionError> {
+    if let Some((numerator, denominator)) = input.split_once('/') {
+        let denominator = val_from_base_system(denominator, base, digit_set, places)?;
+        if denominator.norm_sqr() == bigdecimal::Zero::zero() {
+            return Err(ConversionError::ZeroDenominator);
+        }
+        return Ok(
+            val_from_base_system(numerator, base, digit_set, places)? * denominator.inverse(places)
+        );
+    }
+    let e_is_digit = match base {
+        Base::Positional(base) => {
+            digit_bound(base) > BigDecimal::from(14)
+                || digit_set == &DigitSet::Bijective(Alphabet::Letters)
+        }
+        Base::MixedRadix(_) | Base::Zeckendorf => true,
+    };
+    match (split_exponent(input, e_is_digit)?, base) {
+        ((mantissa, Some(exp)), Base::Positional(base)) => {
+            // A positive exponent scales up the error of the mantissa
+            let places = places + exp.max(0) as i64 * magnitude(&digit_bound(base)).max(1);
+            Ok(val_from_repeating(mantissa, base, digit_set, places)? * base.pow(exp, places))
         }
+        ((_, Some(_)), _) => Err(ConversionError::unsupported(
+            "Exponents only apply to positional bases",
+        )),
+        (_, Base::Positional(base)) => val_from_repeating(input, base, digit_set, places),
+        _ if digit_set != &DigitSet::Standard => Err(ConversionError::unsupported(
+            "Digit sets only apply to positional bases",
+        )),
+        (_, Base::MixedRadix(radices)) => radices.val_from(input, places).map(Complex::from),
+        (_, Base::Zeckendorf) => val_from_zeckendorf(input).map(Complex::from),
+    }
+}
+
+/// Split a trailing exponent off of a number, like the `e-30` of `1.2e-30` or
+/// the `×10^5` of `1×10^5`, along with the power of the base that the rest of
+/// the number is multiplied by.
+///
+/// The `10` of `×10^` is the base itself, which is written `10` in every base,
+/// while the exponent is always a base-10 integer. The shorter `e` only marks
+/// an exponent when `E` can't be a digit, so bases above 14 need `×10^`.
+fn split_exponent(input: &str, e_is_digit: bool) -> Result<(&str, Option<isize>), ConversionError> {
+    let split = ["×10^", "*10^"]
+        .into_iter()
+        .find_map(|marker| input.rsplit_once(marker))
+        .or_else(|| match e_is_digit {
+            true => None,
+            false => input.rsplit_once(['e', 'E']),
+        });
+    let Some((mantissa, exp)) = split else {
+        return Ok((input, None));
+    };
+    match exp.replace('−', "-").parse::<isize>() {
+        Ok(exp) if exp.abs() <= MAX_EXPONENT => Ok((mantissa, Some(exp))),
+        Ok(exp) => Err(ConversionError::ExponentOutOfRange(exp)),
+        Err(_) => Err(ConversionError::InvalidExponent(exp.to_string())),
+    }
+}
+
+/// Parse a value whose trailing fractional digits repeat forever, written in
+/// parentheses like `0.1(6)`.
+///
+/// The repeating block adds the geometric series `block / (bⁿ - 1)`, shifted
+/// past the `m` fractional digits before it.
+fn val_from_repeating(
+    input: &str,
+    base: &Complex,
+    digit_set: &DigitSet,
+    places: i64,
+) -> Result<Complex, ConversionError> {
+    let Some((head, block)) = input.split_once('(') else {
+        return val_from_digit_set(input, base, digit_set, places);
+    };
+    let block = block
+        .strip_suffix(')')
+        .filter(|b| !b.is_empty() && !b.contains(['(', ')', '.']))
+        .ok_or_else(|| {
+            ConversionError::malformed(
+                "Repeating digits must be one block in parentheses at the end of the input",
+            )
+        })?;
+    let (is_negative, unsigned_head) = split_sign(head);
+    let (_, fraction) = unsigned_head
+        .split_once('.')
+        .ok_or_else(|| ConversionError::malformed("Repeating digits must come after the `.`"))?;
+
+    let shift = rep_to_digit_exponent_pairs(fraction).len() as isize;
+    let period = rep_to_digit_exponent_pairs(block).len() as isize;
+    let one = Complex::from(BigDecimal::from(1));
+    let tail = val_from_digit_set(block, base, digit_set, places)?
+        * (base.pow(period, places) - one).inverse(places)
+        * base.pow(-shift, places);
+    let head = val_from_digit_set(head, base, digit_set, places)?;
+    match is_negative {
+        true => Ok(head - tail),
+        false => Ok(head + tail),
     }
+}
 
-    pub fn base_10_value(&self) -> Result<BigDecimal, String> {
-        val_from_base(&self.input_string, &self.input_base).map(|v| v.round(50).normalized())
+/// Write a value in any base. Only real values in real bases greater than 1
+/// with the standard digits can be rounded; everything else is cut off.
+fn val_to_base_system(
+    value: &Complex,
+    base: &Base,
+    digit_set: &DigitSet,
+    precision: isize,
+    rounding: Rounding,
+) -> Result<String, ConversionError> {
+    match base {
+        Base::Positional(base)
+            if value.is_real()
+                && base.is_real()
+                && base.re > bigdecimal::One::one()
+                && digit_set == &DigitSet::Standard =>
+        {
+            val_to_rounded_digits(&value.re, &base.re, precision, rounding).map(|d| d.to_string())
+        }
+        Base::Positional(base) => complex_val_to_base(value, base, digit_set, precision),
+        _ if digit_set != &DigitSet::Standard => Err(ConversionError::unsupported(
+            "Digit sets only apply to positional bases",
+        )),
+        _ if !value.is_real() => Err(ConversionError::unsupported(format!(
+            "Base {base} can only represent real values"
+        ))),
+        Base::MixedRadix(radices) => Ok(radices.val_to(&value.re, precision)),
+        Base::Zeckendorf => val_to_zeckendorf(&value.re).map(|d| d.to_string()),
     }
+}
 
-    pub fn output_string(&self) -> Result<String, String> {
-        self.base_10_value()
-            .map_err(|_| String::from(""))
-            .and_then(|v| val_to_base(&v, &self.output_base, -9))
+/// Write a value in scientific notation: a mantissa in the base times a power
+/// of the base, like `1.2×10^-7`, with the exponent written in its own base.
+///
+/// The exponent `k` is chosen so that `|b|ᵏ ≤ |value| < |b|ᵏ⁺¹`, which leaves
+/// a single integer digit in the mantissa in positive bases. Negative and
+/// complex bases can need a digit or two more.
+fn val_to_scientific(
+    value: &Complex,
+    base: &Base,
+    digit_set: &DigitSet,
+    exponent_base: &Base,
+    precision: isize,
+    rounding: Rounding,
+    places: i64,
+) -> Result<String, ConversionError> {
+    let Base::Positional(positional) = base else {
+        return Err(ConversionError::unsupported(format!(
+            "Base {base} can't be written in scientific notation"
+        )));
+    };
+    let (norm, base_norm) = (value.norm_sqr(), positional.norm_sqr());
+    if norm == bigdecimal::Zero::zero() || base_norm <= bigdecimal::One::one() {
+        return val_to_base_system(value, base, digit_set, precision, rounding);
     }
+    let log10 = |n: &BigDecimal| {
+        let (digits, scale) = n.with_prec(17).into_bigint_and_exponent();
+        digits.to_f64().unwrap().log10() - scale as f64
+    };
+    let mut exp = (log10(&norm) / log10(&base_norm)).floor() as isize;
+    let mantissa = loop {
+        let mantissa = match exp >= 0 {
+            true => value.div(&positional.pow(exp, places), places),
+            false => value.clone() * positional.pow(-exp, places),
+        };
+        match mantissa.norm_sqr() {
+            n if n < bigdecimal::One::one() => exp -= 1,
+            n if n >= base_norm => exp += 1,
+            _ => break mantissa,
+        }
+    };
+    let exponent = Complex::from(BigDecimal::from(exp as i64));
+    Ok(format!(
+        "{}×10^{}",
+        val_to_base_system(&mantissa, base, digit_set, precision, rounding)?,
+        val_to_base_system(
+            &exponent,
+            exponent_base,
+            &DigitSet::Standard,
+            precision,
+            Rounding::Truncate
+        )?
+    ))
+}
 
-    pub fn output_string_accurate(&self) -> Result<String, String> {
-        self.base_10_value()
-            .map_err(|_| String::from(""))
-            .and_then(|v| val_to_base(&v, &self.output_base, -49))
+fn complex_from_popular_strings(s: &str) -> Option<Complex> {
+    match s.to_lowercase().as_str() {
+        "quater-imaginary" => Some(Complex::new(bigdecimal::Zero::zero(), BigDecimal::from(2))),
+        _ => None,
     }
 }
 
-fn val_from_popular_strings(s: &str) -> Option<BigDecimal> {
+/// The integer bases that have names, like `hex`.
+fn integer_from_popular_strings(s: &str) -> Option<i32> {
     match s.to_lowercase().as_str() {
-        "φ" => Some(
-            BigDecimal::from_str("1.61803398874989484820458683436563811772030917980576").unwrap(),
-        ),
-        "phi" => Some(
-            BigDecimal::from_str("1.61803398874989484820458683436563811772030917980576").unwrap(),
-        ),
-        "pi" => Some(
-            BigDecimal::from_str("3.14159265358979323846264338327950288419716939937510").unwrap(),
-        ),
-        "π" => Some(
-            BigDecimal::from_str("3.14159265358979323846264338327950288419716939937510").unwrap(),
-        ),
-        "e" => Some(
-            BigDecimal::from_str("2.71828182845904523536028747135266249775724709369995").unwrap(),
-        ),
-        "sqrt2" => Some(
-            BigDecimal::from_str("1.41421356237309504880168872420969807856967187537694").unwrap(),
-        ),
-        "sqrt3" => Some(
-            BigDecimal::from_str("1.73205080756887729352744634150587236694280525381038").unwrap(),
-        ),
-        "sqrt5" => Some(
-            BigDecimal::from_str("2.23606797749978969640917366873127623544061835961152").unwrap(),
-        ),
-        "two" => Some(BigDecimal::from(2)),
-        "binary" => Some(BigDecimal::from(2)),
-        "three" => Some(BigDecimal::from(3)),
-        "ternary" => Some(BigDecimal::from(3)),
-        "four" => Some(BigDecimal::from(4)),
-        "quaternary" => Some(BigDecimal::from(4)),
-        "five" => Some(BigDecimal::from(5)),
-        "quinary" => Some(BigDecimal::from(5)),
-        "six" => Some(BigDecimal::from(6)),
-        "senary" => Some(BigDecimal::from(6)),
-        "octal" => Some(BigDecimal::from(8)),
-        "eight" => Some(BigDecimal::from(8)),
-        "ten" => Some(BigDecimal::from(10)),
-        "decimal" => Some(BigDecimal::from(10)),
-        "twelve" => Some(BigDecimal::from(12)),
-        "duodecimal" => Some(BigDecimal::from(12)),
-        "dozenal" => Some(BigDecimal::from(12)),
-        "sixteen" => Some(BigDecimal::from(16)),
-        "hex" => Some(BigDecimal::from(16)),
-        "twenty" => Some(BigDecimal::from(20)),
-        "vigesimal" => Some(BigDecimal::from(20)),
-        "sixty" => Some(BigDecimal::from(60)),
-        "sexagesimal" => Some(BigDecimal::from(60)),
+        "two" | "binary" => Some(2),
+        "three" | "ternary" => Some(3),
+        "four" | "quaternary" => Some(4),
+        "five" | "quinary" => Some(5),
+        "six" | "senary" => Some(6),
+        "octal" | "eight" => Some(8),
+        "ten" | "decimal" => Some(10),
+        "twelve" | "duodecimal" | "dozenal" => Some(12),
+        "sixteen" | "hex" => Some(16),
+        "twenty" | "vigesimal" => Some(20),
+        "sixty" | "sexagesimal" => Some(60),
+        "negabinary" => Some(-2),
+        "negaternary" => Some(-3),
+        "negadecimal" => Some(-10),
         _ => None,
     }
 }
 
+/// The value of a named base, with any named constant worked out to the given
+/// number of decimal places.
+fn val_from_popular_strings(s: &str, places: i64) -> Option<BigDecimal> {
+    let (is_negative, s) = split_sign(s);
+    let magnitude = integer_from_popular_strings(s)
+        .map(BigDecimal::from)
+        .or_else(|| constant(s, places));
+    match is_negative {
+        true => magnitude.map(|m| -m),
+        false => magnitude,
+    }
+}
+
+pub fn rounded_complex_string(num: &Complex, hard_limit: Option<NonZeroU64>) -> String {
+    join_parts(
+        num,
+        rounded_string(&num.re, hard_limit),
+        rounded_string(&num.im, hard_limit),
+    )
+}
+
 pub fn rounded_string(num: &BigDecimal, hard_limit: Option<NonZeroU64>) -> String {
     if let Some(hl) = hard_limit {
         if num.digits() > hl.get() {
@@ -132,83 +947,464 @@ pub fn rounded_string(num: &BigDecimal, hard_limit: Option<NonZeroU64>) -> Strin
     }
 }
 
-pub fn pow(base: &BigDecimal, exp: isize) -> BigDecimal {
+/// Raise a base to a power, rounded to the given number of decimal places.
+pub fn pow(base: &BigDecimal, exp: isize, places: i64) -> BigDecimal {
     match exp {
         0 => bigdecimal::One::one(),
-        1 => base.clone().round(50).normalized(),
-        n if n < 0 => pow(base, -exp).inverse().round(50).normalized(),
-        n if n % 2 == 0 => pow(&base.square(), n / 2),
-        n => (base * pow(base, n - 1)).round(50).normalized(),
+        1 => base.clone().round(places).normalized(),
+        n if n < 0 => div(&bigdecimal::One::one(), &pow(base, -exp, places), places),
+        n if n % 2 == 0 => pow(&base.square().round(places), n / 2, places),
+        n => (base * pow(base, n - 1, places)).round(places).normalized(),
     }
 }
 
+/// The number of decimal digits before the radix point, which is negative for
+/// numbers with leading fractional zeros.
+fn magnitude(num: &BigDecimal) -> i64 {
+    num.digits() as i64 - num.as_bigint_and_exponent().1
+}
+
+/// Divide to the given number of decimal places, rather than to the hundred
+/// significant digits that `BigDecimal` division stops at.
+///
+/// Since `n·10⁻ᵃ / (d·10⁻ᵇ) = (n / d)·10ᵇ⁻ᵃ`, this is one integer division,
+/// worked out to one more place so that it can be rounded.
+pub fn div(numerator: &BigDecimal, denominator: &BigDecimal, places: i64) -> BigDecimal {
+    let (n, n_scale) = numerator.as_bigint_and_exponent();
+    let (d, d_scale) = denominator.as_bigint_and_exponent();
+    let shift = places + 1 + d_scale - n_scale;
+    let quotient = match shift >= 0 {
+        true => n * BigInt::from(10).pow(shift as u32) / d,
+        false => n / (d * BigInt::from(10).pow(-shift as u32)),
+    };
+    BigDecimal::new(quotient, places + 1)
+        .round(places)
+        .normalized()
+}
+
+/// The number of decimal places to work out values to, so that the digit with
+/// exponent `precision` is right in a base of the given magnitude.
+pub fn working_places(precision: isize, magnitude: &BigDecimal) -> i64 {
+    let digits_per_place = magnitude.abs().with_scale(0).digits() as i64;
+    (-(precision as i64) * digits_per_place + GUARD_PLACES).max(MIN_WORKING_PLACES)
+}
+
 fn floor(num: &BigDecimal) -> BigDecimal {
     num.with_scale_round(0, bigdecimal::RoundingMode::Floor)
 }
 
-fn base_digits_to_val(digits: &str, base: &BigDecimal) -> Result<BigDecimal, String> {
-    let mut power = base.inverse();
-    let valid_for_base = |char: String| {
-        move |n: u32| -> Result<u32, String> {
-            match base.to_f64().map(|b| (n as f64) < b).unwrap_or(true) {
-                true => Ok(n),
-                false => Err(format!("Invalid digit `{char}` for base-{}", base)),
-            }
-        }
-    };
-    rep_to_digit_exponent_pairs(digits)
+fn ceil(num: &BigDecimal) -> BigDecimal {
+    num.with_scale_round(0, bigdecimal::RoundingMode::Ceiling)
+}
+
+/// Every digit of a base must be less than this bound: the magnitude of a real
+/// base, or the squared magnitude of a complex base.
+pub fn digit_bound(base: &Complex) -> BigDecimal {
+    match base.is_real() {
+        true => base.re.abs(),
+        false => base.norm_sqr(),
+    }
+}
+
+/// The value of a single digit, as split up by `rep_to_digit_exponent_pairs`.
+///
+/// Negative digits can be written with an overbar, like `2̅`, or in brackets,
+/// like `[-2]`. A `T` is also `-1`, as long as it isn't already a digit of the
+/// base.
+pub fn digit_value(char: &str, base: &Complex) -> Result<i64, ConversionError> {
+    if let Some(negated) = char.strip_suffix(OVERBAR) {
+        return digit_value(negated, base).map(|n| -n);
+    }
+    if char.eq_ignore_ascii_case("t") && digit_bound(base) <= BigDecimal::from(29) {
+        return Ok(-1);
+    }
+    Alphabet::Numerals
+        .digit_value(&char.replace('−', "-"))
+        .ok_or_else(|| ConversionError::UnrecognizedDigit {
+            digit: char.to_string(),
+            position: None,
+        })
+}
+
+fn parse_digit(char: &str, base: &Complex) -> Result<i64, ConversionError> {
+    digit_value(char, base).and_then(|n| match BigDecimal::from(n.abs()) < digit_bound(base) {
+        true => Ok(n),
+        false => Err(ConversionError::InvalidDigit {
+            digit: char.to_string(),
+            base: format!("base-{base}"),
+            position: None,
+        }),
+    })
+}
+
+/// The value of a string of digits by Horner's method. Each rounding error is
+/// scaled up by the base once for every digit after it, so every step keeps
+/// an extra decimal place per digit.
+fn base_digits_to_val(
+    digits: &str,
+    base: &BigDecimal,
+    places: i64,
+) -> Result<BigDecimal, ConversionError> {
+    let complex_base = Complex::from(base.clone());
+    let pairs = rep_to_digit_exponent_pairs(digits);
+    let places = places + pairs.len() as i64 * magnitude(base).max(1);
+    pairs
         .into_iter()
-        .rev()
         .try_fold(bigdecimal::Zero::zero(), |sum: BigDecimal, (char, _)| {
-            power *= base;
-            char.parse()
-                .or_else(|_| u32::from_str_radix(&char, 36))
-                .map_err(|_| format!("Unrecognized digit in input: {char}"))
-                .and_then(valid_for_base(char))
-                .map(|int| sum + int * power.clone())
+            parse_digit(&char, &complex_base)
+                .map(|int| (sum * base + int).round(places).normalized())
         })
-        .map(|n| n.round(32).normalized())
 }
 
-fn val_from_base(input: &str, base: &BigDecimal) -> Result<BigDecimal, String> {
-    if base <= &bigdecimal::One::one() {
-        return Err("Input base must be greater than 1".to_string());
+fn val_from_base(
+    input: &str,
+    base: &BigDecimal,
+    places: i64,
+) -> Result<BigDecimal, ConversionError> {
+    if base.abs() <= bigdecimal::One::one() {
+        return Err(ConversionError::BaseOutOfRange {
+            base: base.to_string(),
+        });
     }
-    match input.split('.').collect::<Vec<_>>()[..] {
+    let (is_negative, input) = split_sign(input);
+    let magnitude = match input.split('.').collect::<Vec<_>>()[..] {
         [] => Ok(bigdecimal::Zero::zero()),
-        [positive] => base_digits_to_val(positive, base),
-        [positive, negative] => base_digits_to_val(positive, base).and_then(|integer| {
-            base_digits_to_val(negative, base).and_then(|fractional| {
-                negative
-                    .chars()
-                    .count()
+        [positive] => base_digits_to_val(positive, base, places),
+        [positive, negative] => base_digits_to_val(positive, base, places).and_then(|integer| {
+            base_digits_to_val(negative, base, places).and_then(|fractional| {
+                rep_to_digit_exponent_pairs(negative)
+                    .len()
                     .try_into()
-                    .map(|exp| integer + fractional / (pow(base, exp)))
-                    .map_err(|e| e.to_string())
+                    .map(|exp| integer + div(&fractional, &pow(base, exp, places), places))
+                    .map_err(|e| ConversionError::malformed(e.to_string()))
             })
         }),
-        _ => Err("The input may have at most one `.`".to_string()),
+        _ => Err(ConversionError::TooManyRadixPoints { position: None }),
+    }?;
+    match is_negative {
+        true => Ok(-magnitude),
+        false => Ok(magnitude),
+    }
+}
+
+/// Parse a value written in a real or complex base.
+///
+/// In a real base the value may have an imaginary part, written like `11+100i`
+/// with both parts in that base. The `i` is only treated as the imaginary unit
+/// when it can't be a digit, so bases above 18 can only express real values.
+fn complex_val_from_base(
+    input: &str,
+    base: &Complex,
+    places: i64,
+) -> Result<Complex, ConversionError> {
+    if base.is_real() {
+        let base = &base.re;
+        return match split_complex(input) {
+            Some((re, im)) if base.abs() <= BigDecimal::from(18) => {
+                let re = match re {
+                    "" => bigdecimal::Zero::zero(),
+                    re => val_from_base(re, base, places)?,
+                };
+                let im = match split_sign(im) {
+                    (false, "") => bigdecimal::One::one(),
+                    (true, "") => -BigDecimal::from(1),
+                    _ => val_from_base(im, base, places)?,
+                };
+                Ok(Complex::new(re, im))
+            }
+            _ => val_from_base(input, base, places).map(Complex::from),
+        };
+    }
+    if base.norm_sqr() <= bigdecimal::One::one() {
+        return Err(ConversionError::BaseOutOfRange {
+            base: base.to_string(),
+        });
+    }
+    if input.matches('.').count() > 1 {
+        return Err(ConversionError::TooManyRadixPoints { position: None });
+    }
+    let (is_negative, input) = split_sign(input);
+    let magnitude = rep_to_digit_exponent_pairs(input).into_iter().try_fold(
+        Complex::from(BigDecimal::from(0)),
+        |sum, (char, exp)| {
+            parse_digit(&char, base).map(|int| sum + base.pow(exp, places) * BigDecimal::from(int))
+        },
+    )?;
+    match is_negative {
+        true => Ok(-magnitude),
+        false => Ok(magnitude),
+    }
+}
+
+/// Split a leading `-` or `+` off of a representation, returning whether the
+/// representation is negative along with the unsigned remainder.
+pub fn split_sign(rep: &str) -> (bool, &str) {
+    match rep.strip_prefix(['-', '−']) {
+        Some(magnitude) => (true, magnitude),
+        None => (false, rep.strip_prefix('+').unwrap_or(rep)),
+    }
+}
+
+/// A combining overline, which marks the digit before it as negative.
+pub static OVERBAR: char = '\u{0305}';
+
+/// Written in place of an output digit that couldn't be worked out for sure.
+pub static UNCERTAIN: char = '?';
+
+/// Written for zero in bijective numeration, which writes it with no digits.
+pub static EMPTY: char = 'ε';
+
+/// The digits of a representation, each paired with its exponent, ordered from
+/// most to least significant.
+struct Digits {
+    is_negative: bool,
+    pairs: Vec<(i64, isize)>,
+    is_elided: bool,
+    /// The exponent of the first digit of a repeating block that runs to the
+    /// end of the digits
+    repeating_from: Option<isize>,
+    /// Write the digit `-1` as `T`, which is only unambiguous in bases that
+    /// don't already use `T` as a digit
+    negative_one_as_t: bool,
+    /// Write no digits at all as `EMPTY`, for digit sets without a zero
+    empty_as_epsilon: bool,
+    alphabet: Alphabet,
+    /// The exponent of the first digit that could be wrong, from which on the
+    /// digits are written as `UNCERTAIN`
+    uncertain_from: Option<isize>,
+}
+
+impl Digits {
+    fn new(pairs: Vec<(i64, isize)>, is_elided: bool) -> Self {
+        Self {
+            is_negative: false,
+            pairs,
+            is_elided,
+            repeating_from: None,
+            negative_one_as_t: false,
+            empty_as_epsilon: false,
+            alphabet: Alphabet::Numerals,
+            uncertain_from: None,
+        }
+    }
+
+    /// Collect digits given in any order, filling in zeros for any missing
+    /// exponents and dropping insignificant leading and trailing zeros.
+    fn from_positions(positions: Vec<(i64, isize)>, is_elided: bool) -> Self {
+        let top = positions.iter().map(|&(_, e)| e).max().unwrap_or(0).max(0);
+        let bottom = positions.iter().map(|&(_, e)| e).min().unwrap_or(0).min(0);
+        let mut pairs: Vec<_> = (bottom..=top).rev().map(|e| (0, e)).collect();
+        for (digit, exp) in positions {
+            pairs[(top - exp) as usize].0 = digit;
+        }
+        let leading = pairs.iter().take_while(|&&(d, e)| d == 0 && e > 0).count();
+        let trailing = pairs
+            .iter()
+            .rev()
+            .take_while(|&&(d, e)| d == 0 && e < 0)
+            .count();
+        pairs.truncate(pairs.len() - trailing);
+        pairs.drain(..leading);
+        Self::new(pairs, is_elided)
+    }
+}
+
+impl fmt::Display for Digits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.empty_as_epsilon && self.pairs.is_empty() {
+            return write!(f, "{EMPTY}");
+        }
+        if self.is_negative {
+            write!(f, "-")?;
+        }
+        for &(digit, exp) in &self.pairs {
+            if exp == -1 {
+                write!(f, ".")?;
+            }
+            if Some(exp) == self.repeating_from {
+                write!(f, "(")?;
+            }
+            match digit {
+                _ if self.uncertain_from.is_some_and(|from| exp <= from) => {
+                    write!(f, "{UNCERTAIN}")?
+                }
+                -1 if self.negative_one_as_t => write!(f, "T")?,
+                d if d < 0 => write!(f, "{}{OVERBAR}", self.alphabet.symbol(-d))?,
+                d => write!(f, "{}", self.alphabet.symbol(d))?,
+            }
+        }
+        if self.repeating_from.is_some() {
+            write!(f, ")")?;
+        }
+        if self.is_elided {
+            write!(f, "…")?; // ellide
+        }
+        Ok(())
+    }
+}
+
+/// Write a real or complex value in a real or complex base.
+///
+/// A complex value in a real base is written as separate real and imaginary
+/// parts, like `11+100i`.
+fn complex_val_to_base(
+    value: &Complex,
+    base: &Complex,
+    digit_set: &DigitSet,
+    precision: isize,
+) -> Result<String, ConversionError> {
+    if base.is_real() {
+        let to_base = |v: &BigDecimal| match digit_set {
+            DigitSet::Standard => val_to_base(v, &base.re, precision),
+            DigitSet::Lazy | DigitSet::QuasiGreedy => {
+                val_to_expansion(v, &base.re, digit_set, precision).map(|d| d.to_string())
+            }
+            _ => val_to_digit_set(v, &base.re, digit_set, precision).map(|d| d.to_string()),
+        };
+        return match value.is_real() {
+            true => to_base(&value.re),
+            false => Ok(join_parts(value, to_base(&value.re)?, to_base(&value.im)?)),
+        };
+    }
+    if base.norm_sqr() <= bigdecimal::One::one() {
+        return Err(ConversionError::BaseOutOfRange {
+            base: base.to_string(),
+        });
     }
+    if digit_set != &DigitSet::Standard {
+        return Err(ConversionError::unsupported(
+            "Complex bases only support the standard digits",
+        ));
+    }
+    let digits = if base.re == bigdecimal::Zero::zero() {
+        val_to_imaginary_base(value, &base.im, precision)?
+    } else if base.im.abs() == bigdecimal::One::one()
+        && base.re.is_integer()
+        && base.re < bigdecimal::Zero::zero()
+    {
+        val_to_gaussian_base(value, base, precision)
+    } else {
+        return Err(ConversionError::unsupported(format!(
+            "Output base {base} isn't supported; try a base like 2i or -1+i"
+        )));
+    };
+    Ok(digits.to_string())
 }
 
-fn digit_to_string(digit: usize) -> String {
-    static DIGITS: [&str; 36] = [
-        "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "A", "B", "C", "D", "E", "F", "G", "H",
-        "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z",
-    ];
-    match DIGITS.get(digit) {
-        Some(s) => s.to_string(),
-        None => format!("[{digit}]"),
+/// Write a value in a purely imaginary base `ri`.
+///
+/// Since `(ri)² = -r²`, the even positions hold the real part written in base
+/// `-r²`, and the odd positions hold the imaginary part divided by `r`, also
+/// written in base `-r²`.
+fn val_to_imaginary_base(
+    value: &Complex,
+    r: &BigDecimal,
+    precision: isize,
+) -> Result<Digits, ConversionError> {
+    let ceil_half = |n: isize| -(-n).div_euclid(2);
+    let squared_base = -r.square();
+    let places = working_places(precision, r);
+    let real = val_to_digits(&value.re, &squared_base, ceil_half(precision + 1) - 1)?;
+    let imaginary = val_to_digits(
+        &div(&value.im, r, places),
+        &squared_base,
+        ceil_half(precision) - 1,
+    )?;
+    let positions = real
+        .pairs
+        .into_iter()
+        .map(|(d, e)| (d, 2 * e))
+        .chain(imaginary.pairs.into_iter().map(|(d, e)| (d, 2 * e + 1)))
+        .collect();
+    Ok(Digits::from_positions(
+        positions,
+        real.is_elided || imaginary.is_elided,
+    ))
+}
+
+/// Write a value in a base `-n ± i`, in which every Gaussian integer has a
+/// finite representation using the digits `0` through `n²`.
+///
+/// The value is scaled so that every wanted fractional digit becomes an integer
+/// digit, rounded to the nearest Gaussian integer, and then repeatedly divided
+/// by the base. Each digit is the one that makes the division exact.
+fn val_to_gaussian_base(value: &Complex, base: &Complex, precision: isize) -> Digits {
+    let into_bigint = |n: &BigDecimal| {
+        n.with_scale_round(0, bigdecimal::RoundingMode::HalfEven)
+            .into_bigint_and_exponent()
+            .0
+    };
+    let fractional_digits = -(precision + 1);
+    let places = working_places(precision, &base.norm_sqr());
+    let scaled = value.clone() * base.pow(fractional_digits, places);
+    let (mut re, mut im) = (into_bigint(&scaled.re), into_bigint(&scaled.im));
+    let rounding_error =
+        scaled - Complex::new(BigDecimal::from(re.clone()), BigDecimal::from(im.clone()));
+    let is_elided = rounding_error.norm_sqr() > BigDecimal::new(1.into(), 60);
+
+    let n = -into_bigint(&base.re);
+    let sign = into_bigint(&base.im);
+    let norm: BigInt = n.clone() * n.clone() + 1;
+    // Since `sign·i = base + n`, `i` is congruent to `n·sign` modulo the base.
+    let i_residue = n.clone() * sign.clone();
+    let zero = BigInt::from(0);
+    let mut positions = Vec::new();
+    let mut exp = -fractional_digits;
+    while re != zero || im != zero {
+        let digit = ((re.clone() + im.clone() * i_residue.clone()) % norm.clone() + norm.clone())
+            % norm.clone();
+        let shifted = re - digit.clone();
+        // Multiply by the conjugate of the base, then divide by its norm.
+        let next_re = (-(n.clone() * shifted.clone()) + im.clone() * sign.clone()) / norm.clone();
+        let next_im = (-(sign.clone() * shifted) - im * n.clone()) / norm.clone();
+        positions.push((digit.to_i64().unwrap(), exp));
+        (re, im) = (next_re, next_im);
+        exp += 1;
     }
+    Digits::from_positions(positions, is_elided)
+}
+
+fn val_to_base(
+    value: &BigDecimal,
+    base: &BigDecimal,
+    precision: isize,
+) -> Result<String, ConversionError> {
+    val_to_digits(value, base, precision).map(|d| d.to_string())
+}
+
+fn val_to_digits(
+    value: &BigDecimal,
+    base: &BigDecimal,
+    precision: isize,
+) -> Result<Digits, ConversionError> {
+    val_to_digits_to_places(value, base, precision, working_places(precision, base))
 }
 
-fn val_to_base(value: &BigDecimal, base: &BigDecimal, precision: isize) -> Result<String, String> {
-    let mut value = value.clone();
-    if base <= &bigdecimal::One::one() {
-        return Err("Output base must be greater than 1".to_string());
+/// `val_to_digits`, working out each digit to the given number of decimal
+/// places rather than to just enough for the precision.
+fn val_to_digits_to_places(
+    value: &BigDecimal,
+    base: &BigDecimal,
+    precision: isize,
+    places: i64,
+) -> Result<Digits, ConversionError> {
+    let value = value.clone();
+    if base.abs() <= bigdecimal::One::one() {
+        return Err(ConversionError::BaseOutOfRange {
+            base: base.to_string(),
+        });
     }
     if value == bigdecimal::Zero::zero() {
-        return Ok("0".to_owned());
+        return Ok(Digits::from_positions(vec![], false));
+    }
+    if base < &bigdecimal::Zero::zero() {
+        return Ok(val_to_negative_base(value, base, precision));
+    }
+    if value < bigdecimal::Zero::zero() {
+        return val_to_digits_to_places(&-value, base, precision, places).map(|d| Digits {
+            is_negative: true,
+            ..d
+        });
     }
 
     let mut exp = 1;
@@ -218,36 +1414,177 @@ fn val_to_base(value: &BigDecimal, base: &BigDecimal, precision: isize) -> Resul
         power *= power.clone();
     }
     while exp > bigdecimal::Zero::zero()
-        && floor(&((value.clone() / power.clone()) % base)) == bigdecimal::Zero::zero()
+        && floor(&(div(&value, &power, places) % base)) == bigdecimal::Zero::zero()
     {
         exp -= 1;
-        power = power / base;
+        power = div(&power, base, places);
     }
-    let mut output = String::from("");
-    let most_precise = pow(base, precision * 2);
+    // The remainder is kept in fixed point as a multiple of the place value
+    // of the next digit, so that each digit takes a multiplication by the base
+    // rather than a couple of divisions. Each one also multiplies the error of
+    // the remainder by the base, which the places only allow for in decimal,
+    // so there are twice as many binary places. A remainder within a unit in
+    // the last decimal place of the next digit up is rounded to it, as if it
+    // were worked out in decimal.
+    let bits = fixed_point_bits(2 * places);
+    let fixed_base = to_fixed(base, bits);
+    let largest_digit = to_fixed(&(ceil(base) - 1_u32), 0);
+    let tolerance = to_fixed(&BigDecimal::new(1.into(), places), bits);
+    let mut remainder = to_fixed(&div(&value, &power, places), bits);
+    let mut most_precise = to_fixed(
+        &div(&pow(base, precision * 2, places), &power, places),
+        bits,
+    );
+    let mut pairs = Vec::new();
 
-    while (value > most_precise || exp >= 0) && exp >= precision {
+    while (remainder > most_precise || exp >= 0) && exp >= precision {
         if exp == precision {
-            output.push('…'); // ellide
-            return Ok(output);
+            return Ok(Digits::new(pairs, true));
         }
-        let digit = floor(&((value.clone() / power.clone()) % base));
-        value -= digit.clone() * power.clone();
-        if exp == -1 {
-            output.push('.')
+        let digit = ((&remainder + &tolerance) >> bits).min(largest_digit.clone());
+        remainder = (remainder - (&digit << bits)).max(BigInt::from(0));
+        pairs.push((digit.to_i64().unwrap(), exp));
+        exp -= 1;
+        remainder = (remainder * &fixed_base) >> bits;
+        most_precise = (most_precise * &fixed_base) >> bits;
+    }
+
+    Ok(Digits::new(pairs, false))
+}
+
+/// Enough binary places for a fixed-point number to be as precise as the given
+/// number of decimal places, at a little over log₂ 10 bits each.
+fn fixed_point_bits(places: i64) -> usize {
+    (places.max(0) * 10 / 3 + 1) as usize
+}
+
+/// A number in fixed point with the given number of binary places, rounded
+/// toward zero.
+fn to_fixed(num: &BigDecimal, bits: usize) -> BigInt {
+    let (digits, scale) = num.as_bigint_and_exponent();
+    let shifted = digits << bits;
+    match scale >= 0 {
+        true => shifted / BigInt::from(10).pow(scale as u32),
+        false => shifted * BigInt::from(10).pow(-scale as u32),
+    }
+}
+
+/// Write a value in a negative base, which needs no sign.
+///
+/// Digits are chosen with the Ito-Sadahiro rule: the digits following the
+/// radix point always represent a value in `[-|b| / (|b| + 1), 1 / (|b| + 1))`,
+/// so each digit is whatever keeps the remainder inside that interval.
+fn val_to_negative_base(mut value: BigDecimal, base: &BigDecimal, precision: isize) -> Digits {
+    let magnitude = base.abs();
+    let places = working_places(precision, &magnitude);
+    let lower = -div(&magnitude, &(magnitude.clone() + 1_u32), places);
+    let upper = lower.clone() + 1_u32;
+    let max_digit = ceil(&magnitude) - 1_u32;
+
+    let mut exp = 0;
+    let mut power: BigDecimal = bigdecimal::One::one();
+    while {
+        let scaled = div(&value, &(power.clone() * base), places);
+        scaled < lower || scaled >= upper
+    } {
+        exp += 1;
+        power *= base;
+    }
+    let mut pairs = Vec::new();
+    let most_precise = pow(&magnitude, precision * 2, places);
+
+    while (value.abs() > most_precise || exp >= 0) && exp >= precision {
+        if exp == precision {
+            return Digits::new(pairs, true);
         }
-        let dusize = digit.to_usize().unwrap();
-        output.push_str(&digit_to_string(dusize));
+        let digit = floor(&(div(&value, &power, places) - lower.clone()))
+            .max(bigdecimal::Zero::zero())
+            .min(max_digit.clone());
+        value -= digit.clone() * power.clone();
+        pairs.push((digit.to_i64().unwrap(), exp));
         exp -= 1;
-        power = power / base;
+        power = div(&power, base, places);
+    }
+
+    Digits::new(pairs, false)
+}
+
+/// The Fibonacci number weighing the digit at a position of a Zeckendorf
+/// representation: 1, 2, 3, 5, 8, and so on.
+pub fn fibonacci_weight(position: usize) -> BigInt {
+    let (mut a, mut b) = (BigInt::from(1), BigInt::from(2));
+    for _ in 0..position {
+        (a, b) = (b.clone(), a + b);
+    }
+    a
+}
+
+/// Read a sum of Fibonacci numbers, where every `1` adds its position's
+/// Fibonacci weight. Adjacent `1`s are allowed, even though they never appear
+/// in a Zeckendorf representation.
+fn val_from_zeckendorf(input: &str) -> Result<BigDecimal, ConversionError> {
+    let (is_negative, input) = split_sign(input);
+    if input.contains('.') {
+        return Err(ConversionError::unsupported(
+            "Zeckendorf representations can only represent integers",
+        ));
     }
+    let magnitude =
+        input
+            .chars()
+            .rev()
+            .enumerate()
+            .try_fold(BigInt::from(0), |sum, (position, char)| match char {
+                '0' => Ok(sum),
+                '1' => Ok(sum + fibonacci_weight(position)),
+                _ => Err(ConversionError::InvalidDigit {
+                    digit: char.to_string(),
+                    base: "a Zeckendorf representation".to_string(),
+                    position: None,
+                }),
+            })?;
+    match is_negative {
+        true => Ok(-BigDecimal::from(magnitude)),
+        false => Ok(BigDecimal::from(magnitude)),
+    }
+}
 
-    Ok(output)
+/// Write an integer as a sum of non-consecutive Fibonacci numbers.
+///
+/// Greedily taking the largest Fibonacci number that still fits never takes
+/// two consecutive ones, since their sum is the next Fibonacci number.
+fn val_to_zeckendorf(value: &BigDecimal) -> Result<Digits, ConversionError> {
+    if !value.is_integer() {
+        return Err(ConversionError::unsupported(
+            "Zeckendorf representations can only represent integers",
+        ));
+    }
+    let mut n = value.abs().with_scale(0).into_bigint_and_exponent().0;
+    let mut position = 0;
+    while fibonacci_weight(position + 1) <= n {
+        position += 1;
+    }
+    let mut positions = Vec::new();
+    loop {
+        let weight = fibonacci_weight(position);
+        if weight <= n {
+            n -= weight;
+            positions.push((1, position as isize));
+        }
+        if position == 0 {
+            break;
+        }
+        position -= 1;
+    }
+    Ok(Digits {
+        is_negative: value < &BigDecimal::from(0),
+        ..Digits::from_positions(positions, false)
+    })
 }
 
 pub fn rep_to_digit_exponent_pairs(rep: &str) -> Vec<(String, isize)> {
     let mut digits: Vec<String> = Vec::new();
-    let chars: Vec<char> = rep.chars().collect();
+    let chars: Vec<char> = split_sign(rep).1.chars().collect();
     let mut idx = 0;
     while idx < chars.len() {
         if chars[idx] == '[' {
@@ -261,6 +1598,12 @@ pub fn rep_to_digit_exponent_pairs(rep: &str) -> Vec<(String, isize)> {
             digits.push(chars[idx].to_string());
             idx += 1;
         }
+        if chars.get(idx) == Some(&OVERBAR) {
+            if let Some(digit) = digits.last_mut() {
+                digit.push(OVERBAR);
+            }
+            idx += 1;
+        }
     }
 
     let max_exp: isize =
@@ -281,36 +1624,288 @@ mod tests {
 
     #[test]
     fn base10_conversion() {
-        let decimal = val_from_base("12345", &BigDecimal::from(10)).unwrap();
+        let decimal = val_from_base("12345", &BigDecimal::from(10), 50).unwrap();
         assert_eq!(BigDecimal::from(12345).to_string(), decimal.to_string());
     }
 
     #[test]
     fn fails_with_base1() {
-        let decimal = val_from_base("12345", &BigDecimal::from(1));
+        let decimal = val_from_base("12345", &BigDecimal::from(1), 50);
         assert!(decimal.is_err());
     }
 
     #[test]
     fn fails_with_multiple_periods() {
-        let decimal = val_from_base("12.34.5", &BigDecimal::from(10));
+        let decimal = val_from_base("12.34.5", &BigDecimal::from(10), 50);
         assert!(decimal.is_err());
     }
 
     #[test]
-    fn parses_1_plus_sqrt2_from_base_sqrt2() {
-        let decimal = val_from_base("11", &BigDecimal::from(2).sqrt().unwrap());
+    fn points_out_where_the_input_is_wrong() {
+        let error = |input: &str, base: &str, digits: &str| {
+            BaseConversion {
+                input_digit_set_string: digits.to_string(),
+                ..BaseConversion::for_test(input, base, "10")
+            }
+            .reparsed()
+            .base_10_value()
+            .unwrap_err()
+        };
         assert_eq!(
-            (BigDecimal::from(2).sqrt().unwrap() + 1_u32)
-                .round(20)
-                .to_string(),
-            decimal.unwrap().round(20).to_string()
+            ConversionError::InvalidDigit {
+                digit: "2".to_owned(),
+                base: "base-2".to_owned(),
+                position: Some(4),
+            },
+            error("-1.02", "2", "standard")
         );
-    }
-
+        assert_eq!(
+            ConversionError::TooManyRadixPoints { position: Some(5) },
+            error("12.34.5", "10", "standard")
+        );
+        assert_eq!(Some(2), error("1:60", "time", "standard").position());
+        assert_eq!(Some(2), error("aZ!", "62", "base62").position());
+        assert_eq!(
+            Err(ConversionError::UnknownBase("foo".to_owned())),
+            Base::from_str("foo")
+        );
+        assert!(matches!(
+            Base::from_str("1 +"),
+            Err(ConversionError::InvalidBase(_))
+        ));
+    }
+
+    #[test]
+    fn checks_digits_exactly() {
+        assert_eq!(
+            Ok("10".to_owned()),
+            convert("A", "10.0000000000000000001", "10")
+        );
+        assert!(convert("A", "10", "10").is_err());
+        assert!(convert("[999999999999]", "1000000000000", "10").is_ok());
+        assert!(convert("[1000000000000]", "1000000000000", "10").is_err());
+    }
+
+    #[test]
+    fn carries_digits_in_permissive_mode() {
+        let conversion = |input: &str, input_base: &str, permissive: bool| BaseConversion {
+            permissive,
+            ..BaseConversion::for_test(input, input_base, "10")
+        };
+        assert!(conversion("1[12]", "10", false)
+            .output()
+            .map(|o| o.string)
+            .is_err());
+        let carried = conversion("1[12]e1", "10", true);
+        assert_eq!(Ok("220".to_owned()), carried.output().map(|o| o.string));
+        assert_eq!(
+            Some("Digits past the end of the base were carried: 22e1".to_owned()),
+            carried.input_warning()
+        );
+        assert_eq!(None, conversion("12", "10", true).input_warning());
+    }
+
+    #[test]
+    fn rounds_the_output_and_bounds_its_error() {
+        let conversion = |input: &str, input_base: &str, output_base: &str, rounding| {
+            BaseConversion {
+                precision: 4,
+                rounding,
+                ..BaseConversion::for_test(input, input_base, output_base)
+            }
+            .reparsed()
+        };
+        let exact = conversion("2/3", "10", "10", Rounding::HalfUp);
+        assert_eq!(Ok("0.(6)".to_owned()), exact.output().map(|o| o.string));
+        assert_eq!(
+            Some(BigDecimal::from(0)),
+            exact.output().unwrap().error_bound
+        );
+
+        // Exact values that end past the precision are rounded too
+        let nines = |rounding| conversion("9.999999", "10", "10", rounding);
+        assert_eq!(
+            Ok("9.9999…".to_owned()),
+            nines(Rounding::Truncate).output().map(|o| o.string)
+        );
+        assert_eq!(
+            Ok("10".to_owned()),
+            nines(Rounding::HalfUp).output().map(|o| o.string)
+        );
+        assert_eq!(
+            Some(BigDecimal::from_str("0.000001").unwrap()),
+            nines(Rounding::HalfUp).output().unwrap().error_bound
+        );
+        let tie = |rounding| conversion("0.00005", "10", "10", rounding);
+        assert_eq!(
+            Ok("0".to_owned()),
+            tie(Rounding::HalfEven).output().map(|o| o.string)
+        );
+        assert_eq!(
+            Ok("0.0001".to_owned()),
+            tie(Rounding::HalfUp).output().map(|o| o.string)
+        );
+        let half = BaseConversion {
+            precision: 0,
+            ..conversion("1.5", "10", "10", Rounding::HalfUp)
+        };
+        assert_eq!(Ok("2".to_owned()), half.output().map(|o| o.string));
+
+        let pi = |rounding| conversion("10", "pi", "10", rounding);
+        assert_eq!(
+            Ok("3.1415…".to_owned()),
+            pi(Rounding::Truncate).output().map(|o| o.string)
+        );
+        assert_eq!(
+            Ok("3.1416".to_owned()),
+            pi(Rounding::HalfUp).output().map(|o| o.string)
+        );
+        assert_eq!(
+            Some(BigDecimal::from_str("0.0000074").unwrap()),
+            pi(Rounding::HalfUp).output().unwrap().error_bound
+        );
+        assert_eq!(
+            Some(BigDecimal::from_str("0.000093").unwrap()),
+            pi(Rounding::Truncate).output().unwrap().error_bound
+        );
+
+        let sqrt2 = conversion("1.5", "10", "sqrt2", Rounding::HalfEven);
+        let output = sqrt2.output().map(|o| o.string).unwrap();
+        let bound = sqrt2.output().unwrap().error_bound.unwrap();
+        let base = constant("sqrt2", 60).unwrap();
+        let written = val_from_base(&output, &base, 60).unwrap();
+        assert!((written - BigDecimal::from_str("1.5").unwrap()).abs() <= bound);
+        assert!(bound < pow(&base, -5, 60));
+    }
+
+    #[test]
+    fn makes_sure_of_every_digit() {
+        assert_eq!(
+            Ok("100".to_owned()),
+            convert_with_precision("100", "sqrt2", "sqrt2", 40)
+        );
+        // Just below phi, which is `10` in base phi
+        let nearly_phi = "1.6180339887498948482045868343656381177203091798057628621354486227";
+        assert_eq!(
+            Ok("1.01010101…".to_owned()),
+            convert(nearly_phi, "10", "phi")
+        );
+        assert!(!convert_with_precision("10", "pi", "e", 200)
+            .unwrap()
+            .contains(UNCERTAIN));
+    }
+
+    #[test]
+    fn converts_long_integers() {
+        let ones = "1".repeat(4000);
+        assert_eq!(Ok("F".repeat(1000)), convert(&ones, "2", "16"));
+        assert_eq!(Ok(ones), convert(&"F".repeat(1000), "16", "2"));
+        let power = format!("1{}", "0".repeat(3000));
+        assert_eq!(
+            Ok(format!("1{}", "0".repeat(1000))),
+            convert(&power, "10", "1000")
+        );
+    }
+
+    #[test]
+    fn output_errors_wrap_input_errors() {
+        assert!(matches!(
+            convert("2", "2", "10"),
+            Err(ConversionError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            convert("1.5", "10", "fibonacci"),
+            Err(ConversionError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn reports_settings_that_cant_be_read() {
+        assert_eq!(
+            Err(ConversionError::UnknownBase("foo".to_owned())),
+            convert("1", "10", "foo")
+        );
+        assert!(matches!(
+            convert("1", "10", "1/0"),
+            Err(ConversionError::InvalidBase(_))
+        ));
+        assert_eq!(
+            Err(ConversionError::InvalidBase(Box::new(
+                ConversionError::InvalidMixedRadix(
+                    "Invalid radix `1`; radices must be integers above 1".to_owned()
+                )
+            ))),
+            Base::from_str("60:1")
+        );
+        assert!(matches!(
+            convert("1", "sqrt(-1)", "10"),
+            Err(ConversionError::InvalidInput(e)) if matches!(*e, ConversionError::InvalidBase(_))
+        ));
+        let conversion = |input_base: &str, input_digits: &str| {
+            BaseConversion {
+                input_digit_set_string: input_digits.to_string(),
+                ..BaseConversion::for_test("1", input_base, "10")
+            }
+            .reparsed()
+        };
+        assert!(matches!(
+            conversion("ln(0)", "standard").base_10_value(),
+            Err(ConversionError::InvalidBase(_))
+        ));
+        assert!(matches!(
+            conversion("10", "balanced letters").base_10_value(),
+            Err(ConversionError::InvalidDigitSet(_))
+        ));
+        // The last base that could be read is kept, along with the error
+        let typing = BaseConversion::new_with_defaults(
+            String::from("1"),
+            String::from("2 *"),
+            String::from("standard"),
+            String::from("10"),
+            String::from("standard"),
+            8,
+            String::new(),
+            false,
+            Rounding::Truncate,
+            Some(&BaseConversion::for_test("1", "16", "10")),
+        );
+        assert_eq!(conversion("16", "standard").input_base, typing.input_base);
+        assert_eq!(
+            "Invalid base: The expression ended early at character 4",
+            typing.base_10_value().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn caps_the_size_of_bases() {
+        assert_eq!(
+            Err(ConversionError::BaseOutOfRange {
+                base: "1e100000".to_owned()
+            }),
+            convert("1", "10", "1e100000")
+        );
+        assert!(matches!(
+            convert("1", "10^200", "10"),
+            Err(ConversionError::InvalidInput(e))
+                if matches!(*e, ConversionError::BaseOutOfRange { .. })
+        ));
+        assert_eq!(Ok("10".to_owned()), convert("1e99", "10", "1e99"));
+    }
+
+    #[test]
+    fn parses_1_plus_sqrt2_from_base_sqrt2() {
+        let decimal = val_from_base("11", &BigDecimal::from(2).sqrt().unwrap(), 50);
+        assert_eq!(
+            (BigDecimal::from(2).sqrt().unwrap() + 1_u32)
+                .round(20)
+                .to_string(),
+            decimal.unwrap().round(20).to_string()
+        );
+    }
+
     #[test]
     fn parses_3_from_base_sqrt2() {
-        let decimal = val_from_base("101", &BigDecimal::from(2).sqrt().unwrap());
+        let decimal = val_from_base("101", &BigDecimal::from(2).sqrt().unwrap(), 50);
         assert_eq!(
             (BigDecimal::from(3)).round(20).to_string(),
             decimal.unwrap().round(20).to_string()
@@ -319,7 +1914,7 @@ mod tests {
 
     #[test]
     fn parses_3_from_base_10_3() {
-        let decimal = val_from_base("3", &BigDecimal::from_str("10.3").unwrap());
+        let decimal = val_from_base("3", &BigDecimal::from_str("10.3").unwrap(), 50);
         assert_eq!(
             (BigDecimal::from(3)).to_string(),
             decimal.unwrap().to_string()
@@ -328,7 +1923,7 @@ mod tests {
 
     #[test]
     fn parses_from_base_100() {
-        let decimal = val_from_base("[99]", &BigDecimal::from(100));
+        let decimal = val_from_base("[99]", &BigDecimal::from(100), 50);
         assert_eq!(
             (BigDecimal::from(99)).to_string(),
             decimal.unwrap().to_string()
@@ -337,16 +1932,34 @@ mod tests {
 
     #[test]
     fn parses_decimal() {
-        let decimal = val_from_base("0.12345678", &BigDecimal::from_str("10").unwrap());
+        let decimal = val_from_base("0.12345678", &BigDecimal::from_str("10").unwrap(), 50);
         assert_eq!("0.12345678".to_string(), decimal.unwrap().to_string());
     }
 
     #[test]
     fn parses_decimal_without_leading_zero() {
-        let decimal = val_from_base(".1", &BigDecimal::from_str("10").unwrap());
+        let decimal = val_from_base(".1", &BigDecimal::from_str("10").unwrap(), 50);
         assert_eq!("0.1".to_string(), decimal.unwrap().to_string());
     }
 
+    #[test]
+    fn parses_negative_binary() {
+        let decimal = val_from_base("-101", &BigDecimal::from(2), 50);
+        assert_eq!(Ok(BigDecimal::from(-5)), decimal);
+    }
+
+    #[test]
+    fn parses_explicitly_positive_value() {
+        let decimal = val_from_base("+12.5", &BigDecimal::from(10), 50);
+        assert_eq!(Ok(BigDecimal::from_str("12.5").unwrap()), decimal);
+    }
+
+    #[test]
+    fn parses_negative_fraction_without_leading_zero() {
+        let decimal = val_from_base("-.1", &BigDecimal::from(2), 50);
+        assert_eq!(Ok(BigDecimal::from_str("-0.5").unwrap()), decimal);
+    }
+
     #[test]
     fn round_small_decimal() {
         let decimal = BigDecimal::from_str("0.12345678")
@@ -365,7 +1978,7 @@ mod tests {
 
     #[test]
     fn round_large_integer() {
-        let decimal = rounded_string(&pow(&BigDecimal::from(10), 10), NonZeroU64::new(8));
+        let decimal = rounded_string(&pow(&BigDecimal::from(10), 10, 50), NonZeroU64::new(8));
         assert_eq!("1E+10".to_string(), decimal);
     }
 
@@ -395,7 +2008,7 @@ mod tests {
 
     #[test]
     fn round_small_integer() {
-        let decimal = rounded_string(&pow(&BigDecimal::from(10), 8), NonZeroU64::new(8));
+        let decimal = rounded_string(&pow(&BigDecimal::from(10), 8, 50), NonZeroU64::new(8));
         assert_eq!("100000000".to_string(), decimal);
     }
 
@@ -431,6 +2044,221 @@ mod tests {
         assert_eq!(Ok("0.00000000…".to_owned()), string);
     }
 
+    #[test]
+    fn show_negative_value_in_base_2() {
+        let string = val_to_base(
+            &BigDecimal::from_str("-5.5").unwrap(),
+            &BigDecimal::from(2),
+            -9,
+        );
+        assert_eq!(Ok("-101.1".to_owned()), string);
+    }
+
+    #[test]
+    fn negative_digit_pairs_ignore_the_sign() {
+        let pairs = rep_to_digit_exponent_pairs("-10.1");
+        assert_eq!(
+            vec![
+                ("1".to_owned(), 1),
+                ("0".to_owned(), 0),
+                ("1".to_owned(), -1)
+            ],
+            pairs
+        );
+    }
+
+    #[test]
+    fn parses_negabinary() {
+        let decimal = val_from_base("11010", &BigDecimal::from(-2), 50);
+        assert_eq!(Ok(BigDecimal::from(6)), decimal);
+    }
+
+    #[test]
+    fn fails_with_base_negative_1() {
+        let decimal = val_from_base("101", &BigDecimal::from(-1), 50);
+        assert!(decimal.is_err());
+    }
+
+    #[test]
+    fn show_integers_in_negabinary() {
+        let strings: Vec<_> = [-5, -2, -1, 2, 3, 6]
+            .into_iter()
+            .map(|n| val_to_base(&BigDecimal::from(n), &BigDecimal::from(-2), -9).unwrap())
+            .collect();
+        assert_eq!(vec!["1111", "10", "11", "110", "111", "11010"], strings);
+    }
+
+    #[test]
+    fn show_fraction_in_negabinary() {
+        let string = val_to_base(
+            &BigDecimal::from_str("0.5").unwrap(),
+            &BigDecimal::from(-2),
+            -9,
+        );
+        assert_eq!(Ok("1.1".to_owned()), string);
+    }
+
+    #[test]
+    fn show_15_in_negadecimal() {
+        let string = val_to_base(&BigDecimal::from(15), &BigDecimal::from(-10), -9);
+        assert_eq!(Ok("195".to_owned()), string);
+    }
+
+    #[test]
+    fn round_trip_through_negative_phi() {
+        let base = -val_from_popular_strings("phi", 50).unwrap();
+        let string = val_to_base(&BigDecimal::from(-3), &base, -120).unwrap();
+        let value = val_from_base(string.trim_end_matches('…'), &base, 50).unwrap();
+        assert_eq!(BigDecimal::from(-3), value.round(20));
+    }
+
+    #[test]
+    fn parses_named_negative_bases() {
+        assert_eq!(
+            Some(BigDecimal::from(-2)),
+            val_from_popular_strings("negabinary", 50)
+        );
+        assert_eq!(
+            val_from_popular_strings("phi", 50).map(|phi| -phi),
+            val_from_popular_strings("-φ", 50)
+        );
+    }
+
+    fn complex(s: &str) -> Complex {
+        Complex::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn parses_complex_value_in_base_10() {
+        let value = complex_val_from_base("3+4i", &complex("10"), 50);
+        assert_eq!(Ok(complex("3+4i")), value);
+    }
+
+    #[test]
+    fn parses_complex_value_in_base_2() {
+        let value = complex_val_from_base("-11-100i", &complex("2"), 50);
+        assert_eq!(Ok(complex("-3-4i")), value);
+    }
+
+    #[test]
+    fn parses_i_as_a_digit_in_base_36() {
+        let value = complex_val_from_base("1i", &complex("36"), 50);
+        assert_eq!(Ok(complex("54")), value);
+    }
+
+    #[test]
+    fn parses_quater_imaginary() {
+        let value = complex_val_from_base("10.2", &complex("2i"), 50);
+        assert_eq!(Ok(complex("i")), value);
+    }
+
+    #[test]
+    fn fails_with_invalid_quater_imaginary_digit() {
+        assert!(complex_val_from_base("4", &complex("2i"), 50).is_err());
+    }
+
+    #[test]
+    fn show_complex_value_in_base_2() {
+        let string = complex_val_to_base(&complex("-3-4i"), &complex("2"), &DigitSet::Standard, -9);
+        assert_eq!(Ok("-11-100i".to_owned()), string);
+    }
+
+    #[test]
+    fn show_values_in_quater_imaginary() {
+        let strings: Vec<_> = ["4", "-1", "i", "3+4i"]
+            .into_iter()
+            .map(|v| {
+                complex_val_to_base(&complex(v), &complex("2i"), &DigitSet::Standard, -9).unwrap()
+            })
+            .collect();
+        assert_eq!(vec!["10300", "103", "10.2", "23"], strings);
+    }
+
+    #[test]
+    fn show_values_in_base_negative_1_plus_i() {
+        let strings: Vec<_> = ["2", "-1", "i", "0.5"]
+            .into_iter()
+            .map(|v| {
+                complex_val_to_base(&complex(v), &complex("-1+i"), &DigitSet::Standard, -9).unwrap()
+            })
+            .collect();
+        assert_eq!(vec!["1100", "11101", "11", "1.11"], strings);
+    }
+
+    #[test]
+    fn round_trip_through_base_negative_3_minus_i() {
+        let base = complex("-3-i");
+        let string =
+            complex_val_to_base(&complex("123-45i"), &base, &DigitSet::Standard, -9).unwrap();
+        assert_eq!(
+            Ok(complex("123-45i")),
+            complex_val_from_base(&string, &base, 50)
+        );
+    }
+
+    #[test]
+    fn fails_with_unsupported_complex_base() {
+        assert!(
+            complex_val_to_base(&complex("1"), &complex("2+3i"), &DigitSet::Standard, -9).is_err()
+        );
+    }
+
+    fn show_with_digits(
+        value: &str,
+        base: &str,
+        digit_set: &str,
+    ) -> Result<String, ConversionError> {
+        let digit_set = DigitSet::from_str(digit_set).unwrap();
+        complex_val_to_base(&complex(value), &complex(base), &digit_set, -9)
+    }
+
+    #[test]
+    fn show_values_in_balanced_ternary() {
+        let strings: Vec<_> = ["5", "-5", "0.5"]
+            .into_iter()
+            .map(|v| show_with_digits(v, "3", "balanced").unwrap())
+            .collect();
+        assert_eq!(vec!["1TT", "T11", "0.11111111…"], strings);
+    }
+
+    #[test]
+    fn show_6_in_balanced_decimal() {
+        assert_eq!(
+            Ok("14\u{0305}".to_owned()),
+            show_with_digits("6", "10", "balanced")
+        );
+    }
+
+    #[test]
+    fn show_7_in_non_adjacent_form() {
+        assert_eq!(Ok("100T".to_owned()), show_with_digits("7", "2", "naf"));
+        assert!(show_with_digits("7", "3", "naf").is_err());
+    }
+
+    #[test]
+    fn show_value_with_custom_digits() {
+        assert_eq!(Ok("1T0".to_owned()), show_with_digits("6", "3", "{-1,0,1}"));
+        assert!(show_with_digits("2", "3", "{0,1,5}").is_err());
+        assert!(show_with_digits("2", "3.5", "balanced").is_err());
+    }
+
+    #[test]
+    fn parses_negative_digits() {
+        let values: Vec<_> = [
+            ("1TT", "3"),
+            ("14\u{0305}", "10"),
+            ("[-4]", "10"),
+            ("T", "36"),
+        ]
+        .into_iter()
+        .map(|(v, b)| complex_val_from_base(v, &complex(b), 50).unwrap())
+        .collect();
+        assert_eq!(
+            vec![complex("5"), complex("6"), complex("-4"), complex("29")],
+            values
+        );
+    }
+
     #[test]
     fn round_parsing_correctly() {
         let string = val_to_base(
@@ -440,4 +2268,426 @@ mod tests {
         );
         assert_eq!(Ok("3".to_owned()), string);
     }
+
+    fn convert_with_precision(
+        input: &str,
+        input_base: &str,
+        output_base: &str,
+        precision: usize,
+    ) -> Result<String, ConversionError> {
+        BaseConversion {
+            precision,
+            ..BaseConversion::for_test(input, input_base, output_base)
+        }
+        .reparsed()
+        .output()
+        .map(|o| o.string)
+    }
+
+    fn convert_to_scientific(
+        input: &str,
+        input_base: &str,
+        output_base: &str,
+        exponent_base: &str,
+    ) -> Result<String, ConversionError> {
+        BaseConversion {
+            exponent_base_string: exponent_base.to_string(),
+            ..BaseConversion::for_test(input, input_base, output_base)
+        }
+        .reparsed()
+        .output()
+        .map(|o| o.string)
+    }
+
+    fn convert(
+        input: &str,
+        input_base: &str,
+        output_base: &str,
+    ) -> Result<String, ConversionError> {
+        convert_with_precision(input, input_base, output_base, 8)
+    }
+
+    #[test]
+    fn converts_between_mixed_radices() {
+        assert_eq!(Ok("5025.6".to_owned()), convert("1:23:45.6", "time", "10"));
+        assert_eq!(
+            Ok("1:00:00:00".to_owned()),
+            convert("24:00:00", "hms", "dhms")
+        );
+        assert_eq!(
+            Ok("3:4:1:0:1:0".to_owned()),
+            convert("463", "10", "factorial")
+        );
+        assert!(convert("1:23", "time", "2i").is_ok());
+    }
+
+    #[test]
+    fn fails_to_write_complex_values_in_mixed_radices() {
+        assert!(convert("1+i", "10", "time").is_err());
+    }
+
+    #[test]
+    fn show_integers_in_zeckendorf() {
+        let strings: Vec<_> = ["0", "1", "4", "12", "-100"]
+            .into_iter()
+            .map(|v| convert(v, "10", "zeckendorf").unwrap())
+            .collect();
+        assert_eq!(vec!["0", "1", "101", "10101", "-1000010100"], strings);
+    }
+
+    #[test]
+    fn parses_fibonacci_sums() {
+        assert_eq!(
+            Ok("100".to_owned()),
+            convert("1000010100", "fibonacci", "10")
+        );
+        assert_eq!(Ok("3".to_owned()), convert("11", "fibonacci", "10"));
+        assert!(convert("102", "fibonacci", "10").is_err());
+        assert!(convert("1.5", "10", "fibonacci").is_err());
+    }
+
+    #[test]
+    fn show_repeating_fractions_exactly() {
+        assert_eq!(Ok("0.(0022)".to_owned()), convert("0.1", "10", "3"));
+        assert_eq!(Ok("0.(142857)".to_owned()), convert("0.1", "7", "10"));
+        assert_eq!(Ok("-A.4".to_owned()), convert("-101.1", "3", "12"));
+    }
+
+    #[test]
+    fn elide_fractions_with_long_periods() {
+        assert_eq!(Ok("11.00100100…".to_owned()), convert("3.14159", "10", "2"));
+    }
+
+    #[test]
+    fn round_trip_repeating_fractions_and_fractions() {
+        assert_eq!(Ok("0.(142857)".to_owned()), convert("1/7", "10", "10"));
+        assert_eq!(Ok("0.1".to_owned()), convert("0.1(6)", "10", "6"));
+        assert_eq!(Ok("0.(3)".to_owned()), convert("0.(1)", "4", "10"));
+        assert_eq!(Ok("-0.5".to_owned()), convert("-1/10", "2", "10"));
+        assert!(convert("1/0", "10", "2").is_err());
+        assert!(convert("0.(1", "10", "2").is_err());
+        assert!(convert("1(2)", "10", "2").is_err());
+    }
+
+    #[test]
+    fn parses_repeating_digits_in_irrational_bases() {
+        let value = val_from_base_system(
+            "0.(01)",
+            &Base::from_str("sqrt2").unwrap(),
+            &DigitSet::Standard,
+            50,
+        );
+        assert_eq!(Ok(complex("1")), value.map(|v| v.round(40).normalized()));
+        let base = Base::from_str("sqrt2").unwrap();
+        assert!(val_from_base_system("1(01)", &base, &DigitSet::Standard, 50).is_err());
+    }
+
+    #[test]
+    fn convert_long_binary_fractions_to_hex_exactly() {
+        let binary = format!("0.{}1", "10".repeat(150));
+        let hex = format!("0.{}8", "A".repeat(75));
+        assert_eq!(Ok(hex), convert_with_precision(&binary, "2", "16", 76));
+        let cut = format!("0.{}…", "A".repeat(8));
+        assert_eq!(Ok(cut), convert(&binary, "2", "16"));
+    }
+
+    #[test]
+    fn convert_exactly_between_rational_bases() {
+        assert_eq!(Ok("1000".to_owned()), convert("3.375", "10", "1.5"));
+        assert_eq!(Ok("-100".to_owned()), convert("-2.25", "10", "1.5"));
+        assert_eq!(Ok("11010".to_owned()), convert("6", "10", "negabinary"));
+    }
+
+    #[test]
+    fn writes_as_many_digits_as_asked_for() {
+        assert_eq!(
+            Ok("0.3333…".to_owned()),
+            convert_with_precision("1/3", "2i", "10", 4)
+        );
+        let third = convert_with_precision("1/3", "10", "2i", 300).unwrap();
+        assert_eq!(
+            300,
+            third.split_once('.').unwrap().1.trim_end_matches('…').len()
+        );
+        assert_eq!(
+            Ok(format!("0.{}…", "3".repeat(80))),
+            convert_with_precision(third.trim_end_matches('…'), "2i", "10", 80)
+        );
+    }
+
+    #[test]
+    fn converts_with_expressions_as_bases() {
+        assert_eq!(
+            convert("10", "10", "phi"),
+            convert("10", "10", "(1+sqrt5)/2")
+        );
+        assert_eq!(Ok("10".to_owned()), convert("3.(3)", "10", "10/3"));
+        assert_eq!(Ok("7".to_owned()), convert("100", "sqrt(7)", "10"));
+    }
+
+    #[test]
+    fn reads_exponents_in_any_base() {
+        assert_eq!(
+            Ok("602214076000000000000000".to_owned()),
+            convert("6.02214076e23", "10", "10")
+        );
+        assert_eq!(Ok("0.00000012".to_owned()), convert("1.2E-7", "10", "10"));
+        assert_eq!(Ok("482".to_owned()), convert("1e2", "16", "10"));
+        assert_eq!(Ok("256".to_owned()), convert("1×10^2", "16", "10"));
+        assert_eq!(Ok("100".to_owned()), convert("1*10^2", "pi", "pi"));
+        assert_eq!(Ok("1.5".to_owned()), convert("1.5e0", "10", "10"));
+        assert_eq!(
+            Err(ConversionError::InvalidExponent("-x".to_owned())),
+            val_from_base_system(
+                "1e-x",
+                &Base::from_str("10").unwrap(),
+                &DigitSet::Standard,
+                50
+            )
+            .map(|v| v.to_string())
+        );
+        assert!(convert("1×10^2", "time", "10").is_err());
+    }
+
+    #[test]
+    fn lists_finite_expansions() {
+        let conversion =
+            |input: &str, output_base: &str| BaseConversion::for_test(input, "10", output_base);
+        assert_eq!(
+            Ok(vec!["1", "0.11", "0.1011"]
+                .into_iter()
+                .map(String::from)
+                .collect()),
+            conversion("1", "phi")
+                .finite_expansions(6)
+                .map(|f| f.expansions)
+        );
+        assert_eq!(
+            Ok(FiniteExpansions {
+                expansions: vec![String::from("-123")],
+                is_truncated: false,
+            }),
+            conversion("-123", "10").finite_expansions(12)
+        );
+        assert!(conversion("123.45", "pi").finite_expansions(12).is_ok());
+        assert!(conversion("1", "2i").finite_expansions(12).is_err());
+    }
+
+    #[test]
+    fn converts_between_alphabets() {
+        let convert_digits = |input: &str, input_digits: &str, base: &str, output_digits: &str| {
+            BaseConversion {
+                input_digit_set_string: input_digits.to_string(),
+                output_digit_set_string: output_digits.to_string(),
+                ..BaseConversion::for_test(input, base, base)
+            }
+            .reparsed()
+            .output()
+            .map(|o| o.string)
+        };
+        // Case-sensitive identifiers survive the round trip
+        assert_eq!(
+            Ok("aZ09zA".to_owned()),
+            convert_digits("aZ09zA", "base62", "62", "base62")
+        );
+        assert_eq!(
+            Ok("[36][61]".to_owned()),
+            convert_digits("az", "base62", "62", "standard")
+        );
+        assert_eq!(
+            Ok("ff.8".to_owned()),
+            convert_digits("FF.8", "standard", "16", "lowercase")
+        );
+        assert_eq!(
+            Ok("10".to_owned()),
+            convert_digits("lO", "crockford", "32", "standard")
+        );
+        assert_eq!(
+            Ok("1a".to_owned()),
+            convert_digits("1A", "standard", "20", "balanced lowercase")
+        );
+        assert_eq!(
+            Ok("bab".to_owned()),
+            convert_digits("101", "standard", "2", "\"ab\"")
+        );
+        assert_eq!(
+            Ok("aa".to_owned()),
+            convert_digits("AA", "standard", "11", "bijective lowercase")
+        );
+        assert_eq!(
+            Ok("ε".to_owned()),
+            convert_digits("0", "standard", "26", "spreadsheet")
+        );
+        assert_eq!(
+            Ok("0".to_owned()),
+            convert_digits("ε", "bijective", "10", "standard")
+        );
+        assert!(DigitSet::parse_with_alphabet("balanced letters").is_err());
+    }
+
+    #[test]
+    fn writes_scientific_notation() {
+        assert_eq!(
+            Ok("1.2×10^-7".to_owned()),
+            convert_to_scientific("0.00000012", "10", "10", "10")
+        );
+        assert_eq!(
+            Ok("-1.2345×10^2".to_owned()),
+            convert_to_scientific("-123.45", "10", "10", "10")
+        );
+        assert_eq!(
+            Ok("1×10^1010000".to_owned()),
+            convert_to_scientific("1e80", "10", "10", "2")
+        );
+        assert_eq!(
+            Ok("0".to_owned()),
+            convert_to_scientific("0", "10", "7", "10")
+        );
+        assert!(convert_to_scientific("1", "10", "time", "10").is_err());
+    }
+
+    #[test]
+    fn writes_tiny_and_huge_values_in_scientific_notation() {
+        assert_eq!(
+            Ok("3.44563231…×10^-66".to_owned()),
+            convert_to_scientific("1e-40", "10", "7", "7")
+        );
+        assert_eq!(
+            Ok("3.44563231…×10^-48".to_owned()),
+            convert_to_scientific("1e-40", "10", "7", "10")
+        );
+        let huge = convert_to_scientific("1e80", "10", "phi", "10").unwrap();
+        assert!(huge.starts_with("1.") && huge.ends_with("…×10^382"));
+    }
+
+    #[test]
+    fn writes_500_digits_of_pi_in_base_e() {
+        let pi = convert_with_precision("10", "pi", "e", 500).unwrap();
+        assert_eq!(
+            500,
+            pi.split_once('.').unwrap().1.trim_end_matches('…').len()
+        );
+        // 500 digits in base e pin down π to 217 decimal places
+        let pi_200_places = [
+            "3.141592653589793238462643383279502884197169399375105820974944592307816406286208",
+            "99862803482534211706798214808651328230664709384460955058223172535940812848111745",
+            "028410270193852110555964462294895493038196",
+        ]
+        .concat();
+        assert_eq!(
+            Ok(format!("{pi_200_places}…")),
+            convert_with_precision(pi.trim_end_matches('…'), "e", "10", 200)
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    extern crate test;
+
+    use test::{black_box, Bencher};
+
+    use super::*;
+
+    fn conversion(input: &str, input_base: &str, output_base: &str) -> BaseConversion {
+        BaseConversion {
+            precision: MAX_PRECISION,
+            ..BaseConversion::for_test(input, input_base, output_base)
+        }
+        .reparsed()
+    }
+
+    /// The digits of a positive value in a base greater than 1, worked out in
+    /// decimal the way `val_to_digits` did before it used fixed point, with a
+    /// couple of divisions for every digit, to compare it with.
+    fn val_to_digits_in_decimal(value: &BigDecimal, base: &BigDecimal, precision: isize) -> Digits {
+        let places = working_places(precision, base);
+        let mut value = value.clone();
+        let mut exp = 1;
+        let mut power = base.clone();
+        while power < value {
+            exp *= 2;
+            power *= power.clone();
+        }
+        while exp > 0 && floor(&(div(&value, &power, places) % base)) == bigdecimal::Zero::zero() {
+            exp -= 1;
+            power = div(&power, base, places);
+        }
+        let mut pairs = Vec::new();
+        let most_precise = pow(base, precision * 2, places);
+        while (value > most_precise || exp >= 0) && exp >= precision {
+            if exp == precision {
+                return Digits::new(pairs, true);
+            }
+            let digit = floor(&(div(&value, &power, places) % base));
+            value -= digit.clone() * power.clone();
+            pairs.push((digit.to_i64().unwrap(), exp));
+            exp -= 1;
+            power = div(&power, base, places);
+        }
+        Digits::new(pairs, false)
+    }
+
+    fn pi_and_ten() -> (BigDecimal, BigDecimal) {
+        let places = working_places(-1001, &BigDecimal::from(10));
+        (constant("pi", places).unwrap(), BigDecimal::from(10))
+    }
+
+    fn a_third_and_e() -> (BigDecimal, BigDecimal) {
+        let places = working_places(-1001, &BigDecimal::from(3));
+        let e = constant("e", places).unwrap();
+        (div(&BigDecimal::from(1), &BigDecimal::from(3), places), e)
+    }
+
+    #[test]
+    fn works_out_the_same_digits_in_decimal() {
+        for (value, base) in [pi_and_ten(), a_third_and_e()] {
+            assert_eq!(
+                val_to_digits_in_decimal(&value, &base, -301).to_string(),
+                val_to_base(&value, &base, -301).unwrap()
+            );
+        }
+    }
+
+    #[bench]
+    fn digits_of_pi_in_base_10(b: &mut Bencher) {
+        let (pi, base) = pi_and_ten();
+        b.iter(|| val_to_digits(black_box(&pi), &base, -1001));
+    }
+
+    #[bench]
+    fn digits_of_pi_in_base_10_in_decimal(b: &mut Bencher) {
+        let (pi, base) = pi_and_ten();
+        b.iter(|| val_to_digits_in_decimal(black_box(&pi), &base, -1001));
+    }
+
+    #[bench]
+    fn digits_of_a_third_in_base_e(b: &mut Bencher) {
+        let (third, e) = a_third_and_e();
+        b.iter(|| val_to_digits(black_box(&third), &e, -1001));
+    }
+
+    #[bench]
+    fn digits_of_a_third_in_base_e_in_decimal(b: &mut Bencher) {
+        let (third, e) = a_third_and_e();
+        b.iter(|| val_to_digits_in_decimal(black_box(&third), &e, -1001));
+    }
+
+    #[bench]
+    fn converts_pi_to_base_10(b: &mut Bencher) {
+        let conversion = conversion("10", "pi", "10");
+        b.iter(|| black_box(&conversion).output().map(|o| o.string));
+    }
+
+    #[bench]
+    fn converts_a_tenth_to_base_phi(b: &mut Bencher) {
+        let conversion = conversion("0.1", "10", "phi");
+        b.iter(|| black_box(&conversion).output().map(|o| o.string));
+    }
+
+    #[bench]
+    fn converts_a_long_integer_to_base_10(b: &mut Bencher) {
+        let conversion = conversion(&"1".repeat(100_000), "2", "10");
+        b.iter(|| black_box(&conversion).output().map(|o| o.string));
+    }
 }
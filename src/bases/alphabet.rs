@@ -0,0 +1,286 @@
+use std::{fmt, str::FromStr};
+
+use bigdecimal::BigDecimal;
+
+use super::{ConversionError, OVERBAR};
+
+/// The symbols used to write digits.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Alphabet {
+    /// `0` through `9`, then `A` through `Z` for 10 through 35
+    Numerals,
+    /// `A` for 1 through `Z` for 26, like spreadsheet column names
+    Letters,
+    /// `0` through `9`, then `a` through `z` for 10 through 35
+    Lowercase,
+    /// `0` through `9`, `A` through `Z`, then `a` through `z` for 36 through
+    /// 61, so that case matters
+    Base62,
+    /// Douglas Crockford's Base32 symbols, which leave out `I`, `L`, `O` and
+    /// `U`, reading the first three as `1`, `1` and `0` instead
+    Crockford,
+    /// Any symbols, one for each digit from 0 up
+    Custom(Vec<char>),
+}
+
+static NUMERALS: [&str; 36] = [
+    "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "A", "B", "C", "D", "E", "F", "G", "H", "I",
+    "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z",
+];
+
+static BASE62: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+static CROCKFORD: &str = "0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Characters that mean something else in a representation, so they can't be
+/// the symbols of a custom alphabet.
+static RESERVED: &str = ".-−+()[]/×*^…:,?";
+
+impl FromStr for Alphabet {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(symbols) = s
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .or_else(|| s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+        {
+            let symbols: Vec<char> = symbols.chars().collect();
+            let is_unique = (1..symbols.len()).all(|i| !symbols[..i].contains(&symbols[i]));
+            return match symbols
+                .iter()
+                .find(|&&c| RESERVED.contains(c) || c == OVERBAR || c.is_whitespace())
+            {
+                Some(c) => Err(format!("`{c}` can't be a digit symbol")),
+                None if !is_unique => Err("Each digit needs its own symbol".to_string()),
+                None if symbols.len() < 2 => {
+                    Err("An alphabet needs at least two symbols".to_string())
+                }
+                None => Ok(Self::Custom(symbols)),
+            }
+            .map_err(ConversionError::InvalidDigitSet);
+        }
+        match s.to_lowercase().as_str() {
+            "numerals" | "uppercase" => Ok(Self::Numerals),
+            "letters" => Ok(Self::Letters),
+            "lowercase" => Ok(Self::Lowercase),
+            "base62" => Ok(Self::Base62),
+            "crockford" => Ok(Self::Crockford),
+            _ => Err(ConversionError::InvalidDigitSet(format!(
+                "Unrecognized alphabet: {s}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Alphabet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Numerals => write!(f, "numerals"),
+            Self::Letters => write!(f, "letters"),
+            Self::Lowercase => write!(f, "lowercase"),
+            Self::Base62 => write!(f, "base62"),
+            Self::Crockford => write!(f, "crockford"),
+            Self::Custom(symbols) => write!(f, "\"{}\"", symbols.iter().collect::<String>()),
+        }
+    }
+}
+
+impl Alphabet {
+    /// Write a non-negative digit, bracketing digits without a symbol.
+    pub fn symbol(&self, digit: i64) -> String {
+        let symbol = match self {
+            Self::Numerals => NUMERALS.get(digit as usize).map(|s| s.to_string()),
+            Self::Letters => (digit > 0)
+                .then(|| NUMERALS.get(digit as usize + 9))
+                .flatten()
+                .map(|s| s.to_string()),
+            Self::Lowercase => NUMERALS.get(digit as usize).map(|s| s.to_lowercase()),
+            Self::Base62 => BASE62.chars().nth(digit as usize).map(String::from),
+            Self::Crockford => CROCKFORD.chars().nth(digit as usize).map(String::from),
+            Self::Custom(symbols) => symbols.get(digit as usize).map(|c| c.to_string()),
+        };
+        match symbol {
+            Some(s) => s,
+            None => format!("[{digit}]"),
+        }
+    }
+
+    /// The digit a single symbol stands for.
+    fn symbol_value(&self, c: char) -> Option<i64> {
+        let position = |symbols: &str, c: char| symbols.chars().position(|s| s == c);
+        match self {
+            Self::Numerals | Self::Lowercase => c.to_digit(36).map(|d| d as usize),
+            Self::Letters => c
+                .is_ascii_alphabetic()
+                .then(|| c.to_digit(36).map(|d| d as usize - 9))
+                .flatten(),
+            Self::Base62 => position(BASE62, c),
+            Self::Crockford => match c.to_ascii_uppercase() {
+                'I' | 'L' => Some(1),
+                'O' => Some(0),
+                c => position(CROCKFORD, c),
+            },
+            Self::Custom(symbols) => symbols.iter().position(|&s| s == c),
+        }
+        .map(|d| d as i64)
+    }
+
+    /// Read a digit that was either written with a symbol, or in brackets as a
+    /// base-10 number (with the brackets already removed).
+    pub fn digit_value(&self, char: &str) -> Option<i64> {
+        let numeral = char
+            .parse()
+            .ok()
+            .or_else(|| i64::from_str_radix(char, 36).ok());
+        let mut chars = char.chars();
+        match (self, chars.next(), chars.next()) {
+            (Self::Numerals, _, _) => numeral,
+            (Self::Letters, _, _) => match char.chars().all(|c| c.is_ascii_alphabetic()) {
+                true => numeral.map(|n| n - 9),
+                false => numeral,
+            },
+            (_, Some(c), None) => self.symbol_value(c).or_else(|| char.parse().ok()),
+            _ => char.parse().ok(),
+        }
+    }
+
+    /// Rewrite a representation written with these symbols in the usual
+    /// numerals, bracketing digits past `Z`, like `[36]`.
+    ///
+    /// Only symbols for digits below the bound are rewritten. Anything else is
+    /// left alone, like the `.`, the `e` of an exponent in a small base, or the
+    /// `i` of an imaginary part, and so is anything after a `×10^`.
+    pub(super) fn to_numerals(&self, rep: &str, bound: &BigDecimal) -> String {
+        if self == &Self::Numerals {
+            return rep.to_string();
+        }
+        let (digits, exponent) = split_exponent(rep);
+        let mut numerals = String::new();
+        let mut is_bracketed = false;
+        for c in digits.chars() {
+            is_bracketed = match c {
+                '[' => true,
+                ']' => false,
+                _ => is_bracketed,
+            };
+            match self.symbol_value(c) {
+                Some(d) if !is_bracketed && &BigDecimal::from(d) < bound => {
+                    numerals.push_str(&Self::Numerals.symbol(d))
+                }
+                _ => numerals.push(c),
+            }
+        }
+        numerals + exponent
+    }
+
+    /// Rewrite a representation written in the usual numerals with these
+    /// symbols instead.
+    ///
+    /// Numerals above the bound can only be a `T` for `-1`. Lowercase letters
+    /// are never digits, and anything after a `×` is an exponent, which is
+    /// left alone.
+    pub(super) fn to_symbols(&self, rep: &str, bound: &BigDecimal) -> String {
+        if self == &Self::Numerals {
+            return rep.to_string();
+        }
+        let (digits, exponent) = split_exponent(rep);
+        let mut symbols = String::new();
+        let mut bracketed: Option<String> = None;
+        for c in digits.chars() {
+            match (c, &mut bracketed) {
+                ('[', _) => bracketed = Some(String::new()),
+                (']', Some(digit)) => {
+                    let digit = digit.parse().map(|d| self.symbol(d));
+                    symbols.push_str(&digit.unwrap_or_default());
+                    bracketed = None;
+                }
+                (c, Some(digit)) => digit.push(c),
+                (c, None) if c.is_ascii_lowercase() => symbols.push(c),
+                (c, None) => match Self::Numerals.symbol_value(c) {
+                    Some(d) if &BigDecimal::from(d) >= bound => {
+                        symbols.push_str(&format!("{}{OVERBAR}", self.symbol(1)))
+                    }
+                    Some(d) => symbols.push_str(&self.symbol(d)),
+                    None => symbols.push(c),
+                },
+            }
+        }
+        symbols + exponent
+    }
+}
+
+/// Split a representation before the `×` or `*` of an exponent, which is
+/// always written in the usual numerals.
+fn split_exponent(rep: &str) -> (&str, &str) {
+    match rep.find(['×', '*']) {
+        Some(i) => rep.split_at(i),
+        None => (rep, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_letters() {
+        let symbols: Vec<_> = [1, 26, 27]
+            .into_iter()
+            .map(|d| Alphabet::Letters.symbol(d))
+            .collect();
+        assert_eq!(vec!["A", "Z", "[27]"], symbols);
+    }
+
+    #[test]
+    fn reads_letters() {
+        let values: Vec<_> = ["a", "Z", "27"]
+            .into_iter()
+            .map(|c| Alphabet::Letters.digit_value(c))
+            .collect();
+        assert_eq!(vec![Some(1), Some(26), Some(27)], values);
+    }
+
+    #[test]
+    fn parses_alphabets() {
+        assert_eq!(Ok(Alphabet::Base62), Alphabet::from_str("Base62"));
+        assert_eq!(
+            Ok(Alphabet::Custom(vec!['x', 'y'])),
+            Alphabet::from_str("\"xy\"")
+        );
+        assert!(Alphabet::from_str("\"x.y\"").is_err());
+        assert!(Alphabet::from_str("\"xx\"").is_err());
+    }
+
+    #[test]
+    fn reads_case_sensitive_and_forgiving_symbols() {
+        assert_eq!(Some(10), Alphabet::Base62.digit_value("A"));
+        assert_eq!(Some(36), Alphabet::Base62.digit_value("a"));
+        assert_eq!(Some(1), Alphabet::Crockford.digit_value("l"));
+        assert_eq!(Some(0), Alphabet::Crockford.digit_value("O"));
+        assert_eq!(Some(27), Alphabet::Crockford.digit_value("V"));
+        assert_eq!(None, Alphabet::Crockford.digit_value("U"));
+    }
+
+    #[test]
+    fn rewrites_representations_between_alphabets() {
+        let base62 = BigDecimal::from(62);
+        assert_eq!("[36]1.A", Alphabet::Base62.to_numerals("a1.A", &base62));
+        assert_eq!("a1.A", Alphabet::Base62.to_symbols("[36]1.A", &base62));
+        let ten = BigDecimal::from(10);
+        assert_eq!("1.2e5", Alphabet::Lowercase.to_numerals("1.2e5", &ten));
+        assert_eq!(
+            "10.1×10^10",
+            Alphabet::Custom(vec!['x', 'y']).to_numerals("yx.y×10^10", &BigDecimal::from(2))
+        );
+        assert_eq!(
+            "yx.(y)…×10^-3",
+            Alphabet::Custom(vec!['x', 'y']).to_symbols("10.(1)…×10^-3", &BigDecimal::from(2))
+        );
+        assert_eq!(
+            format!("1{OVERBAR}0"),
+            Alphabet::Lowercase.to_symbols("T0", &BigDecimal::from(3))
+        );
+    }
+}
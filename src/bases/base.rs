@@ -0,0 +1,149 @@
+use std::{fmt, str::FromStr};
+
+use bigdecimal::{BigDecimal, Zero};
+
+use super::{
+    complex_from_popular_strings, digit_bound, fibonacci_weight, magnitude,
+    rep_to_digit_exponent_pairs, val_from_popular_strings, Complex, ConversionError, DigitSet,
+    Expression, MixedRadix, MAX_BASE_DIGITS, MIN_WORKING_PLACES,
+};
+
+/// How a representation assigns place values to its digits.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Base {
+    /// Every position is worth a power of one (possibly complex) base
+    Positional(Complex),
+    /// Every position has its own radix, like hours:minutes:seconds
+    MixedRadix(MixedRadix),
+    /// Every position is worth a Fibonacci number, and integers are written as
+    /// sums of non-consecutive ones
+    Zeckendorf,
+}
+
+impl From<Complex> for Base {
+    fn from(base: Complex) -> Self {
+        Self::Positional(base)
+    }
+}
+
+impl From<BigDecimal> for Base {
+    fn from(base: BigDecimal) -> Self {
+        Self::Positional(Complex::from(base))
+    }
+}
+
+impl FromStr for Base {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, MIN_WORKING_PLACES)
+    }
+}
+
+impl fmt::Display for Base {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Positional(base) => write!(f, "{base}"),
+            Self::MixedRadix(radices) => write!(f, "{radices}"),
+            Self::Zeckendorf => write!(f, "Zeckendorf"),
+        }
+    }
+}
+
+impl Base {
+    /// Parse a base, evaluating any arithmetic expression like `(1+sqrt5)/2`
+    /// to the given number of decimal places.
+    ///
+    /// A lone name that isn't a known base or constant is an unknown base, a
+    /// list of radices that can't be read keeps the reason why, and anything
+    /// else that can't be evaluated is an invalid expression.
+    /// Positional bases with more than `MAX_BASE_DIGITS` decimal digits before
+    /// the radix point are out of range, since every place of the output takes
+    /// that many more decimal places to work out.
+    pub fn parse(s: &str, places: i64) -> Result<Self, ConversionError> {
+        if matches!(
+            s.trim().to_lowercase().as_str(),
+            "zeckendorf" | "fibonacci" | "fib"
+        ) {
+            return Ok(Self::Zeckendorf);
+        }
+        // Only mixed radices are written with colons, so their errors are kept
+        let radices = MixedRadix::from_str(s);
+        if radices.is_ok() || s.contains(':') {
+            return radices
+                .map(Self::MixedRadix)
+                .map_err(|e| ConversionError::InvalidBase(Box::new(e)));
+        }
+        Complex::from_str(s)
+            .ok()
+            .or_else(|| val_from_popular_strings(s, places).map(Complex::from))
+            .or_else(|| complex_from_popular_strings(s))
+            .map(Ok)
+            .unwrap_or_else(|| {
+                Expression::from_str(s)
+                    .and_then(|e| e.eval(places))
+                    .map(Complex::from)
+                    .map_err(|e| {
+                        let name = s.trim();
+                        match name.starts_with(char::is_alphabetic)
+                            && name.chars().all(char::is_alphanumeric)
+                        {
+                            true => ConversionError::UnknownBase(name.to_string()),
+                            false => ConversionError::InvalidBase(Box::new(e)),
+                        }
+                    })
+            })
+            .and_then(|base| {
+                // The digit bound of a complex base is its squared magnitude
+                let digits = match base.is_real() {
+                    true => magnitude(&digit_bound(&base)),
+                    false => (magnitude(&digit_bound(&base)) + 1) / 2,
+                };
+                match digits > MAX_BASE_DIGITS {
+                    true => Err(ConversionError::BaseOutOfRange {
+                        base: s.trim().to_string(),
+                    }),
+                    false => Ok(Self::Positional(base)),
+                }
+            })
+    }
+
+    /// The place value of the digit with the given exponent.
+    pub fn weight(&self, exp: isize) -> Complex {
+        match self {
+            Self::Positional(base) => base.pow(exp, MIN_WORKING_PLACES),
+            Self::MixedRadix(radices) => Complex::from(radices.weight(exp, MIN_WORKING_PLACES)),
+            Self::Zeckendorf => Complex::from(
+                usize::try_from(exp)
+                    .map(|p| BigDecimal::from(fibonacci_weight(p)))
+                    .unwrap_or_else(|_| BigDecimal::zero()),
+            ),
+        }
+    }
+
+    /// Split a representation into its digits, each paired with its exponent.
+    ///
+    /// The parentheses around repeating digits are dropped, leaving one copy
+    /// of the repeating block.
+    pub fn digit_exponent_pairs(&self, rep: &str) -> Vec<(String, isize)> {
+        match self {
+            Self::Positional(_) => rep_to_digit_exponent_pairs(&rep.replace(['(', ')'], "")),
+            Self::Zeckendorf => rep_to_digit_exponent_pairs(rep),
+            Self::MixedRadix(radices) => radices.digit_exponent_pairs(rep),
+        }
+    }
+
+    /// The value of a single digit, as split up by `digit_exponent_pairs`.
+    pub fn digit_value(&self, char: &str, digit_set: &DigitSet) -> Result<i64, ConversionError> {
+        match self {
+            Self::Positional(base) => digit_set.digit_value(char, base),
+            Self::MixedRadix(_) | Self::Zeckendorf => {
+                char.parse()
+                    .map_err(|_| ConversionError::UnrecognizedDigit {
+                        digit: char.to_string(),
+                        position: None,
+                    })
+            }
+        }
+    }
+}
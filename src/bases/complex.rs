@@ -0,0 +1,250 @@
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+use bigdecimal::{BigDecimal, One, Zero};
+
+use super::div;
+
+/// A complex number with arbitrary precision real and imaginary parts.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Complex {
+    pub re: BigDecimal,
+    pub im: BigDecimal,
+}
+
+impl Complex {
+    pub fn new(re: BigDecimal, im: BigDecimal) -> Self {
+        Self { re, im }
+    }
+
+    pub fn is_real(&self) -> bool {
+        self.im.is_zero()
+    }
+
+    /// The square of the magnitude, `re² + im²`.
+    pub fn norm_sqr(&self) -> BigDecimal {
+        self.re.square() + self.im.square()
+    }
+
+    /// The reciprocal, rounded to the given number of decimal places.
+    pub fn inverse(&self, places: i64) -> Self {
+        let norm_sqr = self.norm_sqr();
+        Self::new(
+            div(&self.re, &norm_sqr, places),
+            -div(&self.im, &norm_sqr, places),
+        )
+    }
+
+    /// The quotient, rounded to the given number of decimal places.
+    pub fn div(&self, divisor: &Self, places: i64) -> Self {
+        let norm_sqr = divisor.norm_sqr();
+        let numerator = self.clone() * Self::new(divisor.re.clone(), -divisor.im.clone());
+        Self::new(
+            div(&numerator.re, &norm_sqr, places),
+            div(&numerator.im, &norm_sqr, places),
+        )
+    }
+
+    pub fn round(&self, round_digits: i64) -> Self {
+        Self::new(self.re.round(round_digits), self.im.round(round_digits))
+    }
+
+    pub fn normalized(&self) -> Self {
+        Self::new(self.re.normalized(), self.im.normalized())
+    }
+
+    /// Raise to a power, rounded to the given number of decimal places.
+    pub fn pow(&self, exp: isize, places: i64) -> Self {
+        match exp {
+            0 => Self::from(BigDecimal::one()),
+            1 => self.round(places).normalized(),
+            n if n < 0 => self.pow(-exp, places).inverse(places),
+            n if n % 2 == 0 => (self.clone() * self.clone())
+                .round(places)
+                .pow(n / 2, places),
+            n => (self.clone() * self.pow(n - 1, places))
+                .round(places)
+                .normalized(),
+        }
+    }
+}
+
+impl From<BigDecimal> for Complex {
+    fn from(re: BigDecimal) -> Self {
+        Self::new(re, BigDecimal::zero())
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re.clone() * rhs.re.clone() - self.im.clone() * rhs.im.clone(),
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl Mul<BigDecimal> for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: BigDecimal) -> Self {
+        Self::new(self.re * rhs.clone(), self.im * rhs)
+    }
+}
+
+impl Neg for Complex {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl Sum for Complex {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::from(BigDecimal::zero()), |sum, n| sum + n)
+    }
+}
+
+/// Split a representation like `3+4i` into its real and imaginary parts.
+///
+/// The imaginary part keeps its sign, and is empty or just a sign when the
+/// coefficient is an implied `1` (as in `-1+i`). Returns `None` if the
+/// representation doesn't end with `i`.
+pub fn split_complex(rep: &str) -> Option<(&str, &str)> {
+    let imaginary = rep.strip_suffix('i')?;
+    let mut depth = 0;
+    let mut split = 0;
+    let mut previous = None;
+    for (idx, char) in imaginary.char_indices() {
+        match char {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            '+' | '-' | '−' if depth == 0 && idx > 0 && !matches!(previous, Some('e' | 'E')) => {
+                split = idx
+            }
+            _ => (),
+        }
+        previous = Some(char);
+    }
+    Some(imaginary.split_at(split))
+}
+
+impl FromStr for Complex {
+    type Err = String;
+
+    /// Parse base-10 complex numbers like `2i`, `-1+i` or `1.5-0.5i`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |s: &str| BigDecimal::from_str(s).map_err(|e| e.to_string());
+        match split_complex(s) {
+            None => parse(s).map(Self::from),
+            Some((re, im)) => {
+                let re = match re {
+                    "" => BigDecimal::zero(),
+                    re => parse(re)?,
+                };
+                let im = match im {
+                    "" | "+" => BigDecimal::one(),
+                    "-" | "−" => -BigDecimal::one(),
+                    im => parse(im)?,
+                };
+                Ok(Self::new(re, im))
+            }
+        }
+    }
+}
+
+/// Join already formatted real and imaginary parts, like `3+4i`.
+pub fn join_parts(value: &Complex, real: String, imaginary: String) -> String {
+    let imaginary = match imaginary.as_str() {
+        "1" => String::from(""),
+        "-1" => String::from("-"),
+        _ => imaginary,
+    };
+    match (value.re.is_zero(), value.im.is_zero()) {
+        (_, true) => real,
+        (true, false) => format!("{imaginary}i"),
+        (false, false) if imaginary.starts_with('-') => format!("{real}{imaginary}i"),
+        (false, false) => format!("{real}+{imaginary}i"),
+    }
+}
+
+impl fmt::Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            join_parts(self, self.re.to_string(), self.im.to_string())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quater_imaginary_base() {
+        let base = Complex::from_str("2i").unwrap();
+        assert_eq!(Complex::new(BigDecimal::zero(), BigDecimal::from(2)), base);
+    }
+
+    #[test]
+    fn parses_implied_imaginary_coefficient() {
+        let base = Complex::from_str("-1+i").unwrap();
+        assert_eq!(Complex::new(BigDecimal::from(-1), BigDecimal::one()), base);
+    }
+
+    #[test]
+    fn parses_exponent_notation_in_parts() {
+        let base = Complex::from_str("1e2-2e-1i").unwrap();
+        assert_eq!(
+            Complex::new(BigDecimal::from(100), BigDecimal::from_str("-0.2").unwrap()),
+            base
+        );
+    }
+
+    #[test]
+    fn fails_to_parse_named_constants() {
+        assert!(Complex::from_str("e").is_err());
+        assert!(Complex::from_str("pi").is_err());
+    }
+
+    #[test]
+    fn powers_of_negative_1_plus_i() {
+        let base = Complex::from_str("-1+i").unwrap();
+        assert_eq!(Complex::from_str("2+2i").unwrap(), base.pow(3, 50));
+        assert_eq!(Complex::from_str("-0.5-0.5i").unwrap(), base.pow(-1, 50));
+    }
+
+    #[test]
+    fn displays_complex_numbers() {
+        let strings: Vec<_> = ["3+4i", "-1+i", "-i", "2.5", "0.5-2i"]
+            .into_iter()
+            .map(|s| Complex::from_str(s).unwrap().to_string())
+            .collect();
+        assert_eq!(vec!["3+4i", "-1+i", "-i", "2.5", "0.5-2i"], strings);
+    }
+}
@@ -0,0 +1,236 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use bigdecimal::{num_bigint::BigInt, BigDecimal, One, Signed, Zero};
+
+use super::{expression::ln, GUARD_PLACES};
+
+/// An irrational constant that can be worked out to any number of decimal
+/// places.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+enum Constant {
+    Pi,
+    E,
+    /// The golden ratio, `(1 + √5) / 2`
+    Phi,
+    /// The square root of an integer
+    Sqrt(u32),
+    /// The real root of `x³ = x + 1`
+    Plastic,
+    /// The silver ratio, `1 + √2`
+    Silver,
+    Ln2,
+    /// The Euler-Mascheroni constant
+    Gamma,
+}
+
+thread_local! {
+    /// The most precise value of each constant worked out so far, along with
+    /// its number of decimal places.
+    static CACHE: RefCell<HashMap<Constant, (i64, BigDecimal)>> = RefCell::new(HashMap::new());
+}
+
+impl Constant {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "π" | "pi" => Some(Self::Pi),
+            "e" => Some(Self::E),
+            "φ" | "phi" => Some(Self::Phi),
+            "sqrt2" => Some(Self::Sqrt(2)),
+            "sqrt3" => Some(Self::Sqrt(3)),
+            "sqrt5" => Some(Self::Sqrt(5)),
+            "sqrt10" => Some(Self::Sqrt(10)),
+            "ρ" | "plastic" => Some(Self::Plastic),
+            "silver" => Some(Self::Silver),
+            "ln2" => Some(Self::Ln2),
+            "γ" | "gamma" => Some(Self::Gamma),
+            _ => None,
+        }
+    }
+
+    /// The value of the constant in fixed point, scaled by `10^places` and
+    /// truncated, give or take a few units in the last place.
+    fn fixed_point(&self, places: i64) -> BigInt {
+        let one = BigInt::from(10).pow(places as u32);
+        match self {
+            // Machin's formula
+            Self::Pi => (atan_inverse(5, &one) * 4_u32 - atan_inverse(239, &one)) * 4_u32,
+            Self::E => {
+                let (mut sum, mut term) = (BigInt::zero(), one);
+                let mut k = 1_u32;
+                while !term.is_zero() {
+                    sum += &term;
+                    term /= k;
+                    k += 1;
+                }
+                sum
+            }
+            Self::Phi => (sqrt(5, &one) + one) / 2_u32,
+            Self::Sqrt(n) => sqrt(*n, &one),
+            Self::Plastic => {
+                // Newton's method on `x³ - x - 1`, starting from 1.5
+                let squared = one.clone() * &one;
+                newton(one.clone() * 3_u32 / 2_u32, |x| {
+                    let f = x.pow(3) / &squared - x - &one;
+                    let df = x.pow(2) * 3_u32 / &one - &one;
+                    x - f * &one / df
+                })
+            }
+            Self::Silver => sqrt(2, &one) + one,
+            // ln 2 = Σ 1 / (k·2ᵏ)
+            Self::Ln2 => {
+                let (mut sum, mut power) = (BigInt::zero(), one / 2_u32);
+                let mut k = 1_u32;
+                while !power.is_zero() {
+                    sum += &power / k;
+                    power /= 2_u32;
+                    k += 1;
+                }
+                sum
+            }
+            Self::Gamma => brent_mcmillan(places, &one),
+        }
+    }
+}
+
+/// Newton's method in fixed point, stopping once a step changes the guess by
+/// at most one unit in the last place.
+fn newton(mut x: BigInt, step: impl Fn(&BigInt) -> BigInt) -> BigInt {
+    loop {
+        let next = step(&x);
+        if (next.clone() - &x).abs() <= BigInt::one() {
+            return next;
+        }
+        x = next;
+    }
+}
+
+/// The square root of `n` in fixed point, by Newton's method starting from
+/// above the root.
+fn sqrt(n: u32, one: &BigInt) -> BigInt {
+    let n = one.clone() * one * n;
+    newton(n.clone() / one, |x| (x + &n / x) / 2_u32)
+}
+
+/// The arctangent of `1/n` in fixed point, from its Taylor series.
+fn atan_inverse(n: u32, one: &BigInt) -> BigInt {
+    let n_squared = BigInt::from(n) * n;
+    let mut power = one / n;
+    let mut sum = BigInt::zero();
+    let mut k = 1_u32;
+    while !power.is_zero() {
+        match k % 4 {
+            1 => sum += &power / k,
+            _ => sum -= &power / k,
+        }
+        power /= &n_squared;
+        k += 2;
+    }
+    sum
+}
+
+/// The Euler-Mascheroni constant in fixed point, by the Brent-McMillan
+/// algorithm, which is accurate to about `4n / ln 10` digits.
+fn brent_mcmillan(places: i64, one: &BigInt) -> BigInt {
+    let n = (places as f64 * std::f64::consts::LN_10 / 4.0).ceil() as u32 + 1;
+    // The terms are negligible past αn, where α(ln α - 1) = 3
+    let terms = (3.6 * n as f64).ceil() as u32;
+    let n_squared = BigInt::from(n) * n;
+    let ln_n = ln(&BigDecimal::from(n), places).unwrap();
+    let mut a = -(ln_n * BigDecimal::from(one.clone()))
+        .with_scale(0)
+        .into_bigint_and_exponent()
+        .0;
+    let mut b = one.clone();
+    let (mut u, mut v) = (a.clone(), b.clone());
+    for k in 1..=terms {
+        b = b * &n_squared / k / k;
+        a = (a * &n_squared / k + &b) / k;
+        u += &a;
+        v += &b;
+    }
+    u * one / v
+}
+
+/// The value of a named constant, rounded to the given number of decimal
+/// places.
+pub(super) fn constant(name: &str, places: i64) -> Option<BigDecimal> {
+    let constant = Constant::from_name(name)?;
+    let places = places.max(0);
+    let cached = CACHE.with(|cache| {
+        cache
+            .borrow()
+            .get(&constant)
+            .filter(|(cached_places, _)| *cached_places >= places)
+            .map(|(_, value)| value.clone())
+    });
+    let value = cached.unwrap_or_else(|| {
+        let guarded = places + GUARD_PLACES;
+        let value = BigDecimal::new(constant.fixed_point(guarded), guarded);
+        CACHE.with(|cache| {
+            cache
+                .borrow_mut()
+                .insert(constant, (guarded, value.clone()))
+        });
+        value
+    });
+    Some(value.round(places).normalized())
+}
+
+/// Whether a name refers to a constant, without working out its value.
+pub(super) fn is_constant(name: &str) -> bool {
+    Constant::from_name(name).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(name: &str, places: i64) -> String {
+        constant(name, places).unwrap().to_string()
+    }
+
+    #[test]
+    fn works_out_constants() {
+        assert_eq!(
+            "3.14159265358979323846264338327950288419716939937511",
+            digits("pi", 50)
+        );
+        assert_eq!(
+            "2.71828182845904523536028747135266249775724709369996",
+            digits("e", 50)
+        );
+        assert_eq!(
+            "1.61803398874989484820458683436563811772030917980576",
+            digits("φ", 50)
+        );
+        assert_eq!(
+            "3.16227766016837933199889354443271853371955513932522",
+            digits("sqrt10", 50)
+        );
+        assert_eq!(
+            "1.32471795724474602596090885447809734073440405690173",
+            digits("plastic", 50)
+        );
+        assert_eq!(
+            "2.41421356237309504880168872420969807856967187537695",
+            digits("silver", 50)
+        );
+        assert_eq!(
+            "0.69314718055994530941723212145817656807550013436026",
+            digits("ln2", 50)
+        );
+        assert_eq!(
+            "0.57721566490153286060651209008240243104215933593992",
+            digits("γ", 50)
+        );
+        assert_eq!(None, constant("tau", 50));
+    }
+
+    #[test]
+    fn works_out_many_digits_of_pi() {
+        let pi = digits("pi", 1000);
+        assert_eq!(1002, pi.len());
+        assert!(pi.ends_with("2164201989"));
+        assert_eq!("3.1416", digits("pi", 4));
+    }
+}
@@ -0,0 +1,527 @@
+use std::{collections::HashSet, fmt, str::FromStr};
+
+use bigdecimal::{num_bigint::BigInt, BigDecimal, ToPrimitive};
+
+use super::{
+    complex_val_from_base, digit_value, div, pow, rep_to_digit_exponent_pairs, split_complex,
+    split_sign, working_places, Alphabet, Complex, ConversionError, Digits, EMPTY,
+};
+
+/// The digits a representation may use.
+#[derive(PartialEq, Clone, Debug)]
+pub enum DigitSet {
+    /// The usual digits, `0` through `⌈|b|⌉ - 1`
+    Standard,
+    /// Digits centered on zero, like `{-1, 0, 1}` in base 3 or `{-4, …, 5}` in
+    /// base 10
+    Balanced,
+    /// The binary non-adjacent form, using `{-1, 0, 1}` in base 2 with no two
+    /// adjacent nonzero digits
+    NonAdjacent,
+    /// An explicit set of digits, which must include exactly one digit for
+    /// each remainder modulo the base
+    Custom(Vec<i64>),
+    /// The standard digits, each as small as it can be while the digits after
+    /// it can still make up the rest of the value
+    Lazy,
+    /// The standard digits, each as large as it can be while leaving something
+    /// for the digits after it, so that the expansion never ends
+    QuasiGreedy,
+    /// Bijective numeration, using the digits `1` through `k` in base `k`
+    /// without a zero, so that every positive integer has exactly one
+    /// representation
+    Bijective(Alphabet),
+}
+
+impl FromStr for DigitSet {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "standard" | "greedy" => Ok(Self::Standard),
+            "lazy" => Ok(Self::Lazy),
+            "quasi-greedy" | "quasigreedy" => Ok(Self::QuasiGreedy),
+            "balanced" => Ok(Self::Balanced),
+            "naf" | "non-adjacent" => Ok(Self::NonAdjacent),
+            "bijective" => Ok(Self::Bijective(Alphabet::Numerals)),
+            "bijective letters" | "letters" | "spreadsheet" => {
+                Ok(Self::Bijective(Alphabet::Letters))
+            }
+            list => list
+                .trim_start_matches('{')
+                .trim_end_matches('}')
+                .split(',')
+                .map(|d| d.trim().replace('−', "-").parse::<i64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map(Self::Custom)
+                .map_err(|_| unrecognized(s)),
+        }
+    }
+}
+
+fn unrecognized(s: &str) -> ConversionError {
+    ConversionError::InvalidDigitSet(format!("Unrecognized digit set: {s}"))
+}
+
+impl fmt::Display for DigitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Standard => write!(f, "standard"),
+            Self::Balanced => write!(f, "balanced"),
+            Self::NonAdjacent => write!(f, "non-adjacent"),
+            Self::Lazy => write!(f, "lazy"),
+            Self::QuasiGreedy => write!(f, "quasi-greedy"),
+            Self::Bijective(Alphabet::Numerals) => write!(f, "bijective"),
+            Self::Bijective(alphabet) => write!(f, "bijective {alphabet}"),
+            Self::Custom(digits) => write!(
+                f,
+                "{{{}}}",
+                digits
+                    .iter()
+                    .map(|d| d.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        }
+    }
+}
+
+impl DigitSet {
+    /// Parse a digit set that may end with the alphabet to write its digits
+    /// with, like `balanced lowercase`, or an alphabet alone for the standard
+    /// digits, like `base62`.
+    ///
+    /// Bijective digits keep their alphabet themselves, so the alphabet that's
+    /// returned along with them is always the usual numerals.
+    pub fn parse_with_alphabet(s: &str) -> Result<(Self, Alphabet), ConversionError> {
+        if let Ok(digit_set) = Self::from_str(s) {
+            return Ok((digit_set, Alphabet::Numerals));
+        }
+        if let Ok(alphabet) = Alphabet::from_str(s) {
+            return Self::with_alphabet(Self::Standard, alphabet);
+        }
+        let (digit_set, alphabet) = s
+            .trim()
+            .rsplit_once(char::is_whitespace)
+            .ok_or_else(|| unrecognized(s))?;
+        Self::with_alphabet(Self::from_str(digit_set)?, Alphabet::from_str(alphabet)?)
+    }
+
+    fn with_alphabet(self, alphabet: Alphabet) -> Result<(Self, Alphabet), ConversionError> {
+        match (self, alphabet) {
+            (Self::Bijective(Alphabet::Numerals), alphabet) => {
+                Ok((Self::Bijective(alphabet), Alphabet::Numerals))
+            }
+            (_, Alphabet::Letters) => Err(ConversionError::InvalidDigitSet(
+                "The letters have no zero, so they're only for bijective digits".to_string(),
+            )),
+            (Self::Bijective(_), _) => Err(ConversionError::InvalidDigitSet(
+                "Spreadsheet digits are always letters".to_string(),
+            )),
+            (digit_set, alphabet) => Ok((digit_set, alphabet)),
+        }
+    }
+
+    /// The value of a single digit, as split up by `rep_to_digit_exponent_pairs`.
+    pub fn digit_value(&self, char: &str, base: &Complex) -> Result<i64, ConversionError> {
+        match self {
+            Self::Bijective(alphabet) => {
+                alphabet
+                    .digit_value(char)
+                    .ok_or_else(|| ConversionError::UnrecognizedDigit {
+                        digit: char.to_string(),
+                        position: None,
+                    })
+            }
+            _ => digit_value(char, base),
+        }
+    }
+
+    /// The digits of the set for an integer base, one for each remainder
+    /// modulo the base.
+    fn residue_digits(&self, base: i64) -> Result<Vec<i64>, ConversionError> {
+        let k = base.abs();
+        match self {
+            Self::Standard | Self::Lazy | Self::QuasiGreedy => Ok((0..k).collect()),
+            Self::Balanced => Ok((-(k - 1) / 2..=k / 2).collect()),
+            Self::NonAdjacent => Err(ConversionError::unsupported(
+                "The non-adjacent form needs base 2",
+            )),
+            Self::Bijective(_) => Ok((1..=k).collect()),
+            Self::Custom(digits) => {
+                let residues: HashSet<_> = digits.iter().map(|d| d.rem_euclid(k)).collect();
+                match residues.len() == digits.len() && digits.len() as i64 == k {
+                    true => Ok(digits.clone()),
+                    false => Err(ConversionError::unsupported(format!(
+                        "The digit set {self} needs exactly one digit for each remainder modulo {k}"
+                    ))),
+                }
+            }
+        }
+    }
+}
+
+/// Write a value in an integer base using a digit set that may include
+/// negative digits.
+///
+/// The value is scaled so that every wanted fractional digit becomes an
+/// integer digit and rounded to the nearest integer. Then each digit is the one
+/// congruent to what remains modulo the base, which makes dividing by the base
+/// exact.
+pub(super) fn val_to_digit_set(
+    value: &BigDecimal,
+    base: &BigDecimal,
+    digit_set: &DigitSet,
+    precision: isize,
+) -> Result<Digits, ConversionError> {
+    let k = match base.is_integer() {
+        true => base.to_i64().ok_or_else(|| {
+            ConversionError::unsupported("The base is too large for this digit set")
+        })?,
+        false => {
+            return Err(ConversionError::unsupported(format!(
+                "The {digit_set} digit set needs an integer base"
+            )))
+        }
+    };
+    if let DigitSet::Bijective(alphabet) = digit_set {
+        return val_to_bijective(value, k, alphabet.clone());
+    }
+    let residue_digits = match digit_set {
+        DigitSet::NonAdjacent if k == 2 => vec![-1, 0, 1],
+        digit_set => digit_set.residue_digits(k)?,
+    };
+    let fractional_digits = -(precision + 1);
+    let scaled = value * pow(base, fractional_digits, working_places(precision, base));
+    let rounded = scaled.with_scale_round(0, bigdecimal::RoundingMode::HalfEven);
+    let is_elided = rounded != scaled;
+    let mut n = rounded.into_bigint_and_exponent().0;
+
+    let zero = BigInt::from(0);
+    let mut seen = HashSet::new();
+    let mut positions = Vec::new();
+    let mut exp = -fractional_digits;
+    while n != zero {
+        if !seen.insert(n.clone()) {
+            return Err(ConversionError::unsupported(format!(
+                "The digit set {digit_set} can't represent {value} in base-{base}"
+            )));
+        }
+        let remainder = (n.clone() % k).to_i64().unwrap().rem_euclid(k.abs());
+        let digit = match digit_set {
+            // Choose ±1 so that the next digit is zero
+            DigitSet::NonAdjacent if remainder == 1 => {
+                2 - (n.clone() % 4_i64).to_i64().unwrap().rem_euclid(4)
+            }
+            _ => *residue_digits
+                .iter()
+                .find(|d| d.rem_euclid(k.abs()) == remainder)
+                .unwrap(),
+        };
+        positions.push((digit, exp));
+        n = (n - digit) / k;
+        exp += 1;
+    }
+    Ok(Digits {
+        negative_one_as_t: k.abs() <= 29,
+        ..Digits::from_positions(positions, is_elided)
+    })
+}
+
+fn bijective_base(base: &BigDecimal) -> Result<i64, ConversionError> {
+    match base.to_i64() {
+        Some(k) if base.is_integer() && k >= 2 => Ok(k),
+        _ => Err(ConversionError::unsupported(
+            "Bijective numeration needs an integer base of at least 2",
+        )),
+    }
+}
+
+/// Write an integer in bijective base-k. Zero has no digits, so it's written
+/// as `EMPTY`.
+fn val_to_bijective(
+    value: &BigDecimal,
+    k: i64,
+    alphabet: Alphabet,
+) -> Result<Digits, ConversionError> {
+    bijective_base(&BigDecimal::from(k))?;
+    if !value.is_integer() {
+        return Err(ConversionError::unsupported(
+            "Bijective numeration can only represent integers",
+        ));
+    }
+    let mut n = value.abs().with_scale(0).into_bigint_and_exponent().0;
+    let zero = BigInt::from(0);
+    let mut pairs = Vec::new();
+    let mut exp = 0;
+    while n > zero {
+        let digit = ((n.clone() - 1_u32) % k).to_i64().unwrap() + 1;
+        pairs.push((digit, exp));
+        n = (n - digit) / k;
+        exp += 1;
+    }
+    pairs.reverse();
+    Ok(Digits {
+        is_negative: value < &BigDecimal::from(0),
+        empty_as_epsilon: true,
+        alphabet,
+        ..Digits::new(pairs, false)
+    })
+}
+
+fn val_from_bijective(
+    input: &str,
+    base: &Complex,
+    alphabet: Alphabet,
+) -> Result<BigDecimal, ConversionError> {
+    let k = match base.is_real() {
+        true => bijective_base(&base.re)?,
+        false => {
+            return Err(ConversionError::unsupported(
+                "Bijective numeration needs an integer base of at least 2",
+            ))
+        }
+    };
+    let (is_negative, input) = split_sign(input);
+    if input.trim() == EMPTY.to_string() {
+        return Ok(BigDecimal::from(0));
+    }
+    if input.contains('.') {
+        return Err(ConversionError::unsupported(
+            "Bijective numeration can only represent integers",
+        ));
+    }
+    let magnitude = rep_to_digit_exponent_pairs(input).into_iter().try_fold(
+        BigInt::from(0),
+        |sum, (char, _)| match alphabet.digit_value(&char) {
+            Some(d) if (1..=k).contains(&d) => Ok(sum * k + d),
+            Some(_) => Err(ConversionError::InvalidDigit {
+                base: format!("bijective base-{k}"),
+                digit: char,
+                position: None,
+            }),
+            None => Err(ConversionError::UnrecognizedDigit {
+                digit: char,
+                position: None,
+            }),
+        },
+    )?;
+    match is_negative {
+        true => Ok(-BigDecimal::from(magnitude)),
+        false => Ok(BigDecimal::from(magnitude)),
+    }
+}
+
+/// Parse a value written with the digits of a set for an integer base, which
+/// can include digits past the end of the base, like the `5` of `{0,1,5}` in
+/// base 3.
+fn val_from_residue_digits(
+    input: &str,
+    base: i64,
+    digit_set: &DigitSet,
+    places: i64,
+) -> Result<BigDecimal, ConversionError> {
+    let digits = digit_set.residue_digits(base)?;
+    let (is_negative, input) = split_sign(input);
+    let (integer, fraction) = match input.split('.').collect::<Vec<_>>()[..] {
+        [integer] => (integer, ""),
+        [integer, fraction] => (integer, fraction),
+        _ => return Err(ConversionError::TooManyRadixPoints { position: None }),
+    };
+    let complex_base = Complex::from(BigDecimal::from(base));
+    let fraction = rep_to_digit_exponent_pairs(fraction);
+    let shift = fraction.len() as isize;
+    let scaled = rep_to_digit_exponent_pairs(integer)
+        .into_iter()
+        .chain(fraction)
+        .try_fold(BigInt::from(0), |sum, (char, _)| {
+            match digit_set.digit_value(&char, &complex_base)? {
+                d if digits.contains(&d) => Ok(sum * base + d),
+                _ => Err(ConversionError::InvalidDigit {
+                    digit: char,
+                    base: format!("base-{base} with the digits {digit_set}"),
+                    position: None,
+                }),
+            }
+        })?;
+    let base = BigDecimal::from(base);
+    let magnitude = div(
+        &BigDecimal::from(scaled),
+        &pow(&base, shift, places),
+        places,
+    );
+    match is_negative {
+        true => Ok(-magnitude),
+        false => Ok(magnitude),
+    }
+}
+
+/// Parse a value written with a digit set. Most digit sets are parsed like the
+/// standard digits, since negative digits are always understood, but balanced
+/// and custom digits in an integer base are read as the digits of the set.
+pub(super) fn val_from_digit_set(
+    input: &str,
+    base: &Complex,
+    digit_set: &DigitSet,
+    places: i64,
+) -> Result<Complex, ConversionError> {
+    let integer_base = match base.is_real() && base.re.is_integer() {
+        true => base.re.to_i64().filter(|k| k.abs() >= 2),
+        false => None,
+    };
+    match (digit_set, integer_base) {
+        (DigitSet::Bijective(alphabet), _) => {
+            val_from_bijective(input, base, alphabet.clone()).map(Complex::from)
+        }
+        (DigitSet::Balanced | DigitSet::Custom(_), Some(k)) => {
+            let read = |part| val_from_residue_digits(part, k, digit_set, places);
+            match split_complex(input) {
+                Some((re, im)) if k.abs() <= 18 => {
+                    let re = match re {
+                        "" => BigDecimal::from(0),
+                        re => read(re)?,
+                    };
+                    let im = match split_sign(im) {
+                        (false, "") => BigDecimal::from(1),
+                        (true, "") => BigDecimal::from(-1),
+                        _ => read(im)?,
+                    };
+                    Ok(Complex::new(re, im))
+                }
+                _ => read(input).map(Complex::from),
+            }
+        }
+        _ => complex_val_from_base(input, base, places),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_named_digit_sets() {
+        assert_eq!(Ok(DigitSet::Balanced), DigitSet::from_str("Balanced"));
+        assert_eq!(Ok(DigitSet::NonAdjacent), DigitSet::from_str("naf"));
+    }
+
+    #[test]
+    fn parses_custom_digit_sets() {
+        assert_eq!(
+            Ok(DigitSet::Custom(vec![-1, 0, 1])),
+            DigitSet::from_str("{-1, 0, 1}")
+        );
+        assert!(DigitSet::from_str("one, two").is_err());
+    }
+
+    #[test]
+    fn balanced_digits_for_even_and_odd_bases() {
+        assert_eq!(Ok(vec![-1, 0, 1]), DigitSet::Balanced.residue_digits(3));
+        assert_eq!(
+            Ok(vec![-4, -3, -2, -1, 0, 1, 2, 3, 4, 5]),
+            DigitSet::Balanced.residue_digits(10)
+        );
+    }
+
+    #[test]
+    fn reads_custom_digits_past_the_base() {
+        let base = Complex::from(BigDecimal::from(3));
+        let custom = DigitSet::from_str("{0,1,5}").unwrap();
+        let written = val_to_digit_set(&BigDecimal::from(15), &base.re, &custom, -9).unwrap();
+        assert_eq!("50", written.to_string());
+        assert_eq!(
+            Ok(Complex::from(BigDecimal::from(15))),
+            val_from_digit_set("50", &base, &custom, 50)
+        );
+        assert!(val_from_digit_set("2", &base, &custom, 50).is_err());
+        assert_eq!(
+            Ok(Complex::from(BigDecimal::from(-2))),
+            val_from_digit_set("T1", &base, &DigitSet::Balanced, 50)
+        );
+    }
+
+    #[test]
+    fn parses_bijective_digit_sets() {
+        assert_eq!(
+            Ok(DigitSet::Bijective(Alphabet::Letters)),
+            DigitSet::from_str("spreadsheet")
+        );
+        assert_eq!(
+            Ok(DigitSet::Bijective(Alphabet::Numerals)),
+            DigitSet::from_str("bijective")
+        );
+    }
+
+    #[test]
+    fn reads_spreadsheet_columns() {
+        let letters = DigitSet::Bijective(Alphabet::Letters);
+        let base = Complex::from(BigDecimal::from(26));
+        let values: Vec<_> = ["A", "Z", "AA", "AZ", "ZZ", "XFD"]
+            .into_iter()
+            .map(|c| val_from_digit_set(c, &base, &letters, 50).unwrap())
+            .collect();
+        let expected: Vec<_> = [1, 26, 27, 52, 702, 16384]
+            .into_iter()
+            .map(|n| Complex::from(BigDecimal::from(n)))
+            .collect();
+        assert_eq!(expected, values);
+    }
+
+    #[test]
+    fn writes_spreadsheet_columns() {
+        let strings: Vec<_> = [1, 26, 27, 52, 702, 16384]
+            .into_iter()
+            .map(|n| {
+                val_to_digit_set(
+                    &BigDecimal::from(n),
+                    &BigDecimal::from(26),
+                    &DigitSet::Bijective(Alphabet::Letters),
+                    -9,
+                )
+                .unwrap()
+                .to_string()
+            })
+            .collect();
+        assert_eq!(vec!["A", "Z", "AA", "AZ", "ZZ", "XFD"], strings);
+    }
+
+    #[test]
+    fn writes_bijective_base_10() {
+        let bijective = DigitSet::Bijective(Alphabet::Numerals);
+        let strings: Vec<_> = [10, 100, -20, 0]
+            .into_iter()
+            .map(|n| {
+                val_to_digit_set(&BigDecimal::from(n), &BigDecimal::from(10), &bijective, -9)
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(vec!["A", "9A", "-1A", "ε"], strings);
+        let base = Complex::from(BigDecimal::from(10));
+        assert_eq!(
+            Ok(Complex::from(BigDecimal::from(0))),
+            val_from_digit_set("ε", &base, &bijective, 50)
+        );
+    }
+
+    #[test]
+    fn bijective_rejects_fractions_and_zero_digits() {
+        let base = Complex::from(BigDecimal::from(10));
+        let bijective = DigitSet::Bijective(Alphabet::Numerals);
+        assert!(val_from_digit_set("1.5", &base, &bijective, 50).is_err());
+        assert!(val_from_digit_set("10", &base, &bijective, 50).is_err());
+        assert!(val_to_digit_set(
+            &BigDecimal::from_str("0.5").unwrap(),
+            &BigDecimal::from(10),
+            &bijective,
+            -9
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_incomplete_custom_digit_sets() {
+        assert!(DigitSet::Custom(vec![0, 1]).residue_digits(3).is_err());
+        assert!(DigitSet::Custom(vec![0, 1, 4]).residue_digits(3).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+
+use bigdecimal::BigDecimal;
+
+use super::{Alphabet, MAX_BASE_DIGITS, MAX_EXPONENT, OVERBAR};
+
+/// Why a value couldn't be read or written.
+#[derive(PartialEq, Clone, Debug)]
+pub enum ConversionError {
+    /// A digit too large for where it was written, like the `2` of `102` in
+    /// base 2
+    InvalidDigit {
+        digit: String,
+        /// What the digit was written in, like `base-2` or `radix 60`
+        base: String,
+        /// The index of the digit among the characters of the input, once
+        /// it's known
+        position: Option<usize>,
+    },
+    /// A character that isn't a digit at all
+    UnrecognizedDigit {
+        digit: String,
+        position: Option<usize>,
+    },
+    /// A second `.` in a number
+    TooManyRadixPoints { position: Option<usize> },
+    /// A base too small to write every value with, like `1` or `i/2`, or
+    /// with digits too large to work with, like `1e1000`
+    BaseOutOfRange { base: String },
+    /// A name that isn't a base or a constant
+    UnknownBase(String),
+    /// A base that couldn't be read or evaluated as an expression, with why
+    InvalidBase(Box<ConversionError>),
+    /// An expression that couldn't be read, like `1 +`, with the index of the
+    /// character where reading went wrong
+    MalformedExpression { reason: String, position: usize },
+    /// An expression without a value, like `ln(0)`, with the reason
+    InvalidExpression(String),
+    /// A digit set or alphabet that couldn't be read, with the reason
+    InvalidDigitSet(String),
+    /// A list of radices that couldn't be read, like `60:1`, with the reason
+    InvalidMixedRadix(String),
+    /// An exponent that isn't a base-10 integer, like the `x` of `1ex`
+    InvalidExponent(String),
+    /// An exponent larger than `MAX_EXPONENT`
+    ExponentOutOfRange(isize),
+    /// A fraction like `1/0`
+    ZeroDenominator,
+    /// Input that isn't laid out the way its base expects, like repeating
+    /// digits that aren't at the end
+    MalformedInput(String),
+    /// A base or digit set that can't do what was asked, like writing a
+    /// fraction in bijective numeration
+    Unsupported(String),
+    /// The output can't be written because the input couldn't be read
+    InvalidInput(Box<ConversionError>),
+    /// An output digit that's too close to changing to work out for certain,
+    /// like the digit after the last one of `1` in base `phi`
+    UncertainDigit { exp: isize },
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDigit { digit, base, .. } => {
+                write!(f, "Invalid digit `{digit}` for {base}")
+            }
+            Self::UnrecognizedDigit { digit, .. } => {
+                write!(f, "Unrecognized digit in input: {digit}")
+            }
+            Self::TooManyRadixPoints { .. } => write!(f, "The input may have at most one `.`"),
+            Self::BaseOutOfRange { base } => {
+                write!(
+                    f,
+                    "Base {base} must have a magnitude greater than 1 and less than \
+                     1e{MAX_BASE_DIGITS}"
+                )
+            }
+            Self::UnknownBase(name) => write!(f, "Unknown base `{name}`"),
+            Self::InvalidBase(error) => write!(f, "Invalid base: {error}"),
+            Self::MalformedExpression { reason, position } => {
+                write!(f, "{reason} at character {}", position + 1)
+            }
+            Self::InvalidExponent(exp) => {
+                write!(f, "The exponent `{exp}` must be a base-10 integer")
+            }
+            Self::ExponentOutOfRange(_) => write!(f, "Exponents can be at most {MAX_EXPONENT}"),
+            Self::ZeroDenominator => write!(f, "The denominator can't be zero"),
+            Self::InvalidExpression(reason)
+            | Self::InvalidDigitSet(reason)
+            | Self::InvalidMixedRadix(reason)
+            | Self::MalformedInput(reason)
+            | Self::Unsupported(reason) => write!(f, "{reason}"),
+            Self::InvalidInput(error) => write!(f, "{error}"),
+            Self::UncertainDigit { exp } => {
+                write!(
+                    f,
+                    "The digit with exponent {exp} can't be worked out for certain"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+impl ConversionError {
+    pub(super) fn unsupported(reason: impl Into<String>) -> Self {
+        Self::Unsupported(reason.into())
+    }
+
+    pub(super) fn malformed(reason: impl Into<String>) -> Self {
+        Self::MalformedInput(reason.into())
+    }
+
+    /// The index of the offending character among the characters of the
+    /// input, when there is one.
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            Self::InvalidDigit { position, .. }
+            | Self::UnrecognizedDigit { position, .. }
+            | Self::TooManyRadixPoints { position } => *position,
+            Self::InvalidInput(error) => error.position(),
+            _ => None,
+        }
+    }
+
+    /// Find where the error is in the input it came from, which was written
+    /// with the given alphabet.
+    ///
+    /// Digits are reported the way they were read, after the alphabet was
+    /// rewritten in the usual numerals, so each digit of the input is
+    /// rewritten the same way to find the first one that matches.
+    pub(super) fn locate(self, input: &str, alphabet: &Alphabet, bound: &BigDecimal) -> Self {
+        let chars: Vec<char> = input.chars().collect();
+        let find_digit = |digit: &str| {
+            let mut idx = 0;
+            while idx < chars.len() {
+                let start = idx;
+                idx = match chars[idx] {
+                    '[' => chars[idx..]
+                        .iter()
+                        .position(|&c| c == ']')
+                        .map_or(chars.len(), |end| idx + end + 1),
+                    _ => idx + 1,
+                };
+                if chars.get(idx) == Some(&OVERBAR) {
+                    idx += 1;
+                }
+                let token: String = chars[start..idx].iter().collect();
+                let token = alphabet.to_numerals(&token, bound).replace(['[', ']'], "");
+                if token == digit {
+                    return Some(start);
+                }
+            }
+            input.find(digit).map(|byte| input[..byte].chars().count())
+        };
+        match self {
+            Self::InvalidDigit {
+                digit,
+                base,
+                position: None,
+            } => Self::InvalidDigit {
+                position: find_digit(&digit),
+                digit,
+                base,
+            },
+            Self::UnrecognizedDigit {
+                digit,
+                position: None,
+            } => Self::UnrecognizedDigit {
+                position: find_digit(&digit),
+                digit,
+            },
+            Self::TooManyRadixPoints { position: None } => Self::TooManyRadixPoints {
+                position: chars
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &c)| c == '.')
+                    .nth(1)
+                    .map(|(idx, _)| idx),
+            },
+            error => error,
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use bigdecimal::{BigDecimal, One, Signed, ToPrimitive, Zero};
+
+use super::{
+    ceil, div, floor, pow, working_places, ConversionError, DigitSet, Digits, GUARD_PLACES,
+};
+
+/// The most expansions `finite_expansions` lists, since values in bases near 1
+/// can have a great many.
+static MAX_EXPANSIONS: usize = 100;
+/// The most digits `finite_expansions` tries, since there can be far too many
+/// combinations of them to try them all even when few add up to the value.
+static MAX_VISITS: usize = 20_000;
+
+/// The largest digit of a base greater than 1, `⌈b⌉ - 1`.
+fn max_digit(base: &BigDecimal) -> BigDecimal {
+    ceil(base) - BigDecimal::one()
+}
+
+/// Write a positive value by choosing each digit in turn, from the digit at
+/// `exp` down.
+///
+/// `choose` gets the remaining value divided by the place value of the digit,
+/// rounded a little to absorb the error of the steps before it, and returns
+/// the digit to use there. Lower digits get fewer places, since dividing by a
+/// smaller place value magnifies the error.
+fn expand(
+    value: &BigDecimal,
+    base: &BigDecimal,
+    precision: isize,
+    mut exp: isize,
+    choose: impl Fn(BigDecimal) -> BigDecimal,
+) -> Digits {
+    let places = working_places(precision, base);
+    let max_digit = max_digit(base);
+    let mut power = pow(base, exp, places);
+    let mut remaining = value.clone();
+    let mut pairs = Vec::new();
+    while !remaining.is_zero() || exp >= 0 {
+        if exp == precision {
+            return Digits::new(pairs, true);
+        }
+        let quotient = div(&remaining, &power, places)
+            .round(working_places(precision - exp, base) - GUARD_PLACES / 2);
+        let digit = choose(quotient)
+            .max(BigDecimal::zero())
+            .min(max_digit.clone());
+        remaining -= digit.clone() * power.clone();
+        pairs.push((digit.to_i64().unwrap(), exp));
+        exp -= 1;
+        power = div(&power, base, places);
+    }
+    Digits::new(pairs, false)
+}
+
+/// Write a value in a base greater than 1 with the standard digits, choosing
+/// the digits lazily or quasi-greedily.
+///
+/// The lazy expansion makes each digit as small as it can be while the digits
+/// after it can still make up the rest of the value, so it's the smallest
+/// expansion. The quasi-greedy expansion makes each digit as large as it can be
+/// while leaving something for the digits after it, so it's the largest
+/// expansion that never ends. Both match the greedy expansion of `val_to_base`
+/// until it ends.
+pub(super) fn val_to_expansion(
+    value: &BigDecimal,
+    base: &BigDecimal,
+    digit_set: &DigitSet,
+    precision: isize,
+) -> Result<Digits, ConversionError> {
+    if base <= &BigDecimal::one() {
+        return Err(ConversionError::unsupported(format!(
+            "The {digit_set} digit set needs a base greater than 1"
+        )));
+    }
+    if value.is_negative() {
+        return val_to_expansion(&-value, base, digit_set, precision).map(|d| Digits {
+            is_negative: true,
+            ..d
+        });
+    }
+    if value.is_zero() {
+        return Ok(Digits::from_positions(vec![], false));
+    }
+
+    let places = working_places(precision, base);
+    let max_digit = max_digit(base);
+    // The most that all the digits below a position can add up to, as a
+    // multiple of the place value of that position
+    let tail = div(&max_digit, &(base - BigDecimal::one()), places);
+    let mut exp = 0;
+    let mut power = BigDecimal::one();
+    match digit_set {
+        DigitSet::Lazy => {
+            while value > &(power.clone() * base * &tail) {
+                exp += 1;
+                power *= base;
+            }
+            Ok(expand(value, base, precision, exp, |q| ceil(&(q - &tail))))
+        }
+        _ => {
+            while &(power.clone() * base) < value {
+                exp += 1;
+                power *= base;
+            }
+            Ok(expand(value, base, precision, exp, |q| {
+                ceil(&q) - BigDecimal::one()
+            }))
+        }
+    }
+}
+
+/// Every finite expansion of a value in a base greater than 1 that uses the
+/// standard digits and at most `max_digits` digits from the leading digit of
+/// the greedy expansion, listed from the greedy expansion down, along with
+/// whether the search stopped early, after `MAX_EXPANSIONS` expansions or
+/// `MAX_VISITS` digits, so there may be more.
+///
+/// Integer bases only have one finite expansion, but non-integer bases can
+/// have many, like `100`, `11` and `10.11` for `φ²` in base-`φ`.
+pub(super) fn finite_expansions(
+    value: &BigDecimal,
+    base: &BigDecimal,
+    max_digits: usize,
+    places: i64,
+) -> Result<(Vec<Digits>, bool), ConversionError> {
+    if base <= &BigDecimal::one() {
+        return Err(ConversionError::unsupported(
+            "Only bases greater than 1 have alternative expansions",
+        ));
+    }
+    if value.is_negative() {
+        return finite_expansions(&-value, base, max_digits, places).map(
+            |(expansions, is_truncated)| {
+                let negated = expansions
+                    .into_iter()
+                    .map(|d| Digits {
+                        is_negative: true,
+                        ..d
+                    })
+                    .collect();
+                (negated, is_truncated)
+            },
+        );
+    }
+    if value.is_zero() {
+        return Ok((vec![Digits::from_positions(vec![], false)], false));
+    }
+
+    let mut top = 0;
+    let mut power = BigDecimal::one();
+    while &(power.clone() * base) <= value {
+        top += 1;
+        power *= base;
+    }
+    while &power > value {
+        top -= 1;
+        power = div(&power, base, places);
+    }
+    let powers: Vec<_> = (0..max_digits)
+        .scan(power, |power, _| {
+            let place_value = power.clone();
+            *power = div(power, base, places);
+            Some(place_value)
+        })
+        .collect();
+    let max_digit = max_digit(base).to_i64().unwrap();
+    // The most the digits after each position can add up to
+    let tails: Vec<_> = (0..max_digits)
+        .map(|i| powers[i + 1..].iter().sum::<BigDecimal>() * BigDecimal::from(max_digit))
+        .collect();
+
+    let mut search = Search {
+        top,
+        powers,
+        tails,
+        max_digit,
+        tolerance: BigDecimal::new(1.into(), places - GUARD_PLACES),
+        found: Vec::new(),
+        visits: 0,
+        is_truncated: false,
+    };
+    search.run(value.clone(), &mut Vec::new());
+    Ok((search.found, search.is_truncated))
+}
+
+/// A depth-first search for the digits that add up to a value exactly, trying
+/// larger digits first.
+struct Search {
+    top: isize,
+    powers: Vec<BigDecimal>,
+    tails: Vec<BigDecimal>,
+    max_digit: i64,
+    tolerance: BigDecimal,
+    found: Vec<Digits>,
+    /// How many digits have been tried
+    visits: usize,
+    /// Whether the search ran out of expansions or visits before it was done
+    is_truncated: bool,
+}
+
+impl Search {
+    fn run(&mut self, remaining: BigDecimal, digits: &mut Vec<i64>) {
+        if self.found.len() == MAX_EXPANSIONS || self.visits == MAX_VISITS {
+            self.is_truncated = true;
+            return;
+        }
+        self.visits += 1;
+        if remaining.abs() <= self.tolerance {
+            let positions = digits.iter().copied().zip((0..).map(|i| self.top - i));
+            self.found
+                .push(Digits::from_positions(positions.collect(), false));
+            return;
+        }
+        let i = digits.len();
+        if i == self.powers.len() {
+            return;
+        }
+        let most = floor(&div(&remaining, &self.powers[i], GUARD_PLACES))
+            .to_i64()
+            .unwrap()
+            .min(self.max_digit);
+        for digit in (0..=most.max(0)).rev() {
+            let rest = remaining.clone() - BigDecimal::from(digit) * &self.powers[i];
+            if rest > self.tails[i].clone() + &self.tolerance {
+                break;
+            }
+            digits.push(digit);
+            self.run(rest, digits);
+            digits.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bases::constant;
+    use std::str::FromStr;
+
+    fn phi() -> BigDecimal {
+        constant("phi", 60).unwrap()
+    }
+
+    fn expansion(value: BigDecimal, base: BigDecimal, digit_set: DigitSet) -> String {
+        val_to_expansion(&value, &base, &digit_set, -9)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn writes_lazy_expansions() {
+        assert_eq!(
+            "0.01111111…",
+            expansion(BigDecimal::one(), phi(), DigitSet::Lazy)
+        );
+        assert_eq!(
+            "0.99999999…",
+            expansion(BigDecimal::one(), BigDecimal::from(10), DigitSet::Lazy)
+        );
+        assert_eq!(
+            "100.11111111…",
+            expansion(BigDecimal::from(5), BigDecimal::from(2), DigitSet::Lazy)
+        );
+    }
+
+    #[test]
+    fn writes_expansions_past_the_rounding_places() {
+        let value = BigDecimal::from_str("1.00000000000001").unwrap();
+        let expansion = val_to_expansion(&value, &BigDecimal::from(10), &DigitSet::Lazy, -20);
+        assert_eq!("1.0000000000000099999…", expansion.unwrap().to_string());
+    }
+
+    #[test]
+    fn writes_quasi_greedy_expansions() {
+        assert_eq!(
+            "0.10101010…",
+            expansion(BigDecimal::one(), phi(), DigitSet::QuasiGreedy)
+        );
+        assert_eq!(
+            "100.11111111…",
+            expansion(
+                BigDecimal::from(5),
+                BigDecimal::from(2),
+                DigitSet::QuasiGreedy
+            )
+        );
+        assert_eq!(
+            "-0.99999999…",
+            expansion(
+                BigDecimal::from(-1),
+                BigDecimal::from(10),
+                DigitSet::QuasiGreedy
+            )
+        );
+    }
+
+    #[test]
+    fn lists_finite_expansions() {
+        let phi_squared = phi().square();
+        let (expansions, is_truncated) = finite_expansions(&phi_squared, &phi(), 5, 60).unwrap();
+        assert!(!is_truncated);
+        let expansions: Vec<_> = expansions.iter().map(|d| d.to_string()).collect();
+        assert_eq!(vec!["100", "11", "10.11"], expansions);
+    }
+
+    #[test]
+    fn integer_bases_have_one_finite_expansion() {
+        let expansions = finite_expansions(&BigDecimal::from(255), &BigDecimal::from(16), 6, 60);
+        assert_eq!(
+            vec!["FF"],
+            expansions
+                .unwrap()
+                .0
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn stops_searching_after_enough_digits() {
+        // In bases near 1 the digits below each one can add up to many times
+        // its place value, so most of the ways to choose them have to be tried
+        let base = BigDecimal::new(11.into(), 1);
+        let (expansions, is_truncated) =
+            finite_expansions(&BigDecimal::one(), &base, 60, 60).unwrap();
+        assert!(is_truncated);
+        assert!(expansions.len() < MAX_EXPANSIONS);
+    }
+}
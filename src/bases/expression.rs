@@ -0,0 +1,610 @@
+use std::str::FromStr;
+
+use bigdecimal::{
+    num_bigint::BigInt, BigDecimal, Context, FromPrimitive, One, Signed, ToPrimitive, Zero,
+};
+use num_rational::BigRational;
+
+use super::{
+    div, integer_from_popular_strings, is_constant, magnitude, pow,
+    rational::rational_from_decimal, val_from_popular_strings, ConversionError, GUARD_PLACES,
+};
+
+/// The most decimal digits a value in an expression may have before the
+/// radix point.
+static MAX_INTEGER_DIGITS: f64 = 10_000.0;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Function {
+    Sqrt,
+    Root,
+    Ln,
+    Exp,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "sqrt" => Some(Self::Sqrt),
+            "root" => Some(Self::Root),
+            "ln" => Some(Self::Ln),
+            "exp" => Some(Self::Exp),
+            _ => None,
+        }
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Self::Root => 2,
+            Self::Sqrt | Self::Ln | Self::Exp => 1,
+        }
+    }
+}
+
+/// An arithmetic expression for a real base, like `(1+sqrt5)/2` or `e^2`.
+///
+/// Expressions support `+ - * / ^`, parentheses, the functions `sqrt(x)`,
+/// `root(n, x)`, `ln(x)` and `exp(x)`, and the named constants like `pi`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Expression(Node);
+
+#[derive(PartialEq, Clone, Debug)]
+enum Node {
+    Number(BigDecimal),
+    Constant(String),
+    Negate(Box<Node>),
+    Binary(Operator, Box<Node>, Box<Node>),
+    Call(Function, Vec<Node>),
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Token {
+    Number(BigDecimal),
+    Name(String),
+    Symbol(char),
+}
+
+fn malformed(reason: String, position: usize) -> ConversionError {
+    ConversionError::MalformedExpression { reason, position }
+}
+
+fn invalid(reason: &str) -> ConversionError {
+    ConversionError::InvalidExpression(reason.to_string())
+}
+
+/// The tokens of an expression, each with the index of its first character.
+fn tokenize(s: &str) -> Result<Vec<(Token, usize)>, ConversionError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().enumerate().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&(_, d)) = chars
+                .peek()
+                .filter(|(_, d)| d.is_ascii_digit() || *d == '.')
+            {
+                number.push(d);
+                chars.next();
+            }
+            let number = BigDecimal::from_str(&number).map_err(|_| {
+                malformed(format!("Invalid number `{number}` in expression"), start)
+            })?;
+            tokens.push((Token::Number(number), start));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut name = String::new();
+            while let Some(&(_, d)) = chars
+                .peek()
+                .filter(|(_, d)| d.is_alphanumeric() || *d == '_')
+            {
+                name.push(d);
+                chars.next();
+            }
+            tokens.push((Token::Name(name), start));
+        } else {
+            let symbol = match c {
+                '−' => '-',
+                '×' | '·' => '*',
+                '÷' => '/',
+                c => c,
+            };
+            match symbol {
+                '+' | '-' | '*' | '/' | '^' | '(' | ')' | ',' => {
+                    tokens.push((Token::Symbol(symbol), start))
+                }
+                _ => return Err(malformed(format!("Unexpected `{c}` in expression"), start)),
+            }
+            chars.next();
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser, from the loosest binding operators to the
+/// tightest: sums, products, signs, powers, and then numbers, names and
+/// parentheses. Powers are right associative, so `2^3^2` is `2^9`.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    /// The number of characters in the expression, where it ends
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    /// An error at the start of the next token, or at the end of the
+    /// expression when there are none left.
+    fn error_here(&self, reason: String) -> ConversionError {
+        let position = self.tokens.get(self.position).map_or(self.end, |&(_, p)| p);
+        malformed(reason, position)
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        match self.peek() == Some(&Token::Symbol(symbol)) {
+            true => {
+                self.position += 1;
+                true
+            }
+            false => false,
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), ConversionError> {
+        match self.eat(symbol) {
+            true => Ok(()),
+            false => Err(self.error_here(format!("Missing `{symbol}` in expression"))),
+        }
+    }
+
+    fn sum(&mut self) -> Result<Node, ConversionError> {
+        let mut node = self.product()?;
+        loop {
+            let operator = match () {
+                _ if self.eat('+') => Operator::Add,
+                _ if self.eat('-') => Operator::Subtract,
+                _ => return Ok(node),
+            };
+            node = Node::Binary(operator, Box::new(node), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Node, ConversionError> {
+        let mut node = self.sign()?;
+        loop {
+            let operator = match () {
+                _ if self.eat('*') => Operator::Multiply,
+                _ if self.eat('/') => Operator::Divide,
+                _ => return Ok(node),
+            };
+            node = Node::Binary(operator, Box::new(node), Box::new(self.sign()?));
+        }
+    }
+
+    fn sign(&mut self) -> Result<Node, ConversionError> {
+        match () {
+            _ if self.eat('-') => Ok(Node::Negate(Box::new(self.sign()?))),
+            _ if self.eat('+') => self.sign(),
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Node, ConversionError> {
+        let base = self.atom()?;
+        match self.eat('^') {
+            true => Ok(Node::Binary(
+                Operator::Power,
+                Box::new(base),
+                Box::new(self.sign()?),
+            )),
+            false => Ok(base),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Node, ConversionError> {
+        let start = self.tokens.get(self.position).map_or(self.end, |&(_, p)| p);
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Node::Number(n)),
+            Some(Token::Symbol('(')) => {
+                let node = self.sum()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            Some(Token::Name(name)) => match Function::from_name(&name) {
+                Some(function) => {
+                    self.expect('(')?;
+                    let mut arguments = vec![self.sum()?];
+                    while self.eat(',') {
+                        arguments.push(self.sum()?);
+                    }
+                    self.expect(')')?;
+                    match arguments.len() == function.arity() {
+                        true => Ok(Node::Call(function, arguments)),
+                        false => Err(malformed(
+                            format!("`{name}` takes {} argument(s)", function.arity()),
+                            start,
+                        )),
+                    }
+                }
+                None if integer_from_popular_strings(&name).is_some() || is_constant(&name) => {
+                    Ok(Node::Constant(name))
+                }
+                None => Err(malformed(
+                    format!("Unknown name `{name}` in expression"),
+                    start,
+                )),
+            },
+            Some(Token::Symbol(c)) => {
+                Err(malformed(format!("Unexpected `{c}` in expression"), start))
+            }
+            None => Err(malformed("The expression ended early".to_string(), start)),
+        }
+    }
+}
+
+impl FromStr for Expression {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            position: 0,
+            end: s.chars().count(),
+        };
+        let node = parser.sum()?;
+        let unexpected = match parser.peek() {
+            None => return Ok(Self(node)),
+            Some(Token::Number(n)) => n.to_string(),
+            Some(Token::Name(name)) => name.clone(),
+            Some(Token::Symbol(c)) => c.to_string(),
+        };
+        Err(parser.error_here(format!("Unexpected `{unexpected}` in expression")))
+    }
+}
+
+impl Expression {
+    /// The value of the expression, rounded to the given number of decimal
+    /// places.
+    pub fn eval(&self, places: i64) -> Result<BigDecimal, ConversionError> {
+        self.0
+            .eval(places + GUARD_PLACES)
+            .map(|v| v.round(places).normalized())
+    }
+
+    /// The exact value of the expression, if it only takes rational values.
+    pub fn exact(&self) -> Option<BigRational> {
+        self.0.exact()
+    }
+}
+
+impl Node {
+    fn eval(&self, places: i64) -> Result<BigDecimal, ConversionError> {
+        match self {
+            Self::Number(n) => Ok(n.clone()),
+            Self::Constant(name) => val_from_popular_strings(name, places)
+                .ok_or_else(|| invalid(&format!("Unknown name `{name}` in expression"))),
+            Self::Negate(node) => node.eval(places).map(|v| -v),
+            Self::Binary(operator, left, right) => {
+                let (left, right) = (left.eval(places)?, right.eval(places)?);
+                match operator {
+                    Operator::Add => Ok(left + right),
+                    Operator::Subtract => Ok(left - right),
+                    Operator::Multiply => Ok((left * right).round(places)),
+                    Operator::Divide if right.is_zero() => {
+                        Err(invalid("Division by zero in expression"))
+                    }
+                    Operator::Divide => Ok(div(&left, &right, places)),
+                    Operator::Power => power(&left, &right, places),
+                }
+            }
+            Self::Call(function, arguments) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|a| a.eval(places))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match (function, &arguments[..]) {
+                    (Function::Sqrt, [x]) => root(x, &BigDecimal::from(2), places),
+                    (Function::Root, [n, x]) => root(x, n, places),
+                    (Function::Ln, [x]) => ln(x, places),
+                    (Function::Exp, [x]) => exp(x, places),
+                    _ => Err(invalid("Wrong number of arguments in expression")),
+                }
+            }
+        }
+    }
+
+    fn exact(&self) -> Option<BigRational> {
+        match self {
+            Self::Number(n) => Some(rational_from_decimal(n)),
+            Self::Constant(name) => {
+                integer_from_popular_strings(name).map(|n| BigRational::from_integer(n.into()))
+            }
+            Self::Negate(node) => node.exact().map(|v| -v),
+            Self::Binary(operator, left, right) => {
+                let (left, right) = (left.exact()?, right.exact()?);
+                match operator {
+                    Operator::Add => Some(left + right),
+                    Operator::Subtract => Some(left - right),
+                    Operator::Multiply => Some(left * right),
+                    Operator::Divide => (!right.is_zero()).then(|| left / right),
+                    Operator::Power => {
+                        let exp = right.is_integer().then(|| right.to_integer().to_i32())??;
+                        let too_big = left.numer().bits().max(left.denom().bits())
+                            * u64::from(exp.unsigned_abs())
+                            > 4 * MAX_INTEGER_DIGITS as u64;
+                        (!too_big && !(left.is_zero() && exp < 0)).then(|| left.pow(exp))
+                    }
+                }
+            }
+            Self::Call(function, arguments) => match (function, &arguments[..]) {
+                (Function::Sqrt, [x]) => exact_root(&x.exact()?, 2),
+                (Function::Root, [n, x]) => {
+                    let n = n
+                        .exact()
+                        .filter(|n| n.is_integer())?
+                        .to_integer()
+                        .to_u32()?;
+                    exact_root(&x.exact()?, n)
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+/// The `n`th root of a rational value, if it's also rational.
+fn exact_root(value: &BigRational, n: u32) -> Option<BigRational> {
+    if n == 0 || (value.is_negative() && n.is_multiple_of(2)) {
+        return None;
+    }
+    let root = |i: &BigInt| Some(i.nth_root(n)).filter(|r| &r.pow(n) == i);
+    Some(BigRational::new(root(value.numer())?, root(value.denom())?))
+}
+
+/// How many decimal digits come before the radix point of `exp(log)`.
+fn digits_of_exp(log: &BigDecimal) -> Result<i64, ConversionError> {
+    let digits = log.with_prec(17).to_f64().unwrap_or(f64::INFINITY) / std::f64::consts::LN_10;
+    match digits <= MAX_INTEGER_DIGITS {
+        true => Ok(digits.ceil().max(0.0) as i64),
+        false => Err(invalid("The value of the expression is too large")),
+    }
+}
+
+fn power(base: &BigDecimal, exp: &BigDecimal, places: i64) -> Result<BigDecimal, ConversionError> {
+    if exp.is_integer() {
+        if base.is_zero() && exp.is_negative() {
+            return Err(invalid("Division by zero in expression"));
+        }
+        if !base.is_zero() {
+            let log =
+                BigDecimal::from_f64(base.abs().with_prec(17).to_f64().unwrap_or(f64::MAX).ln())
+                    .unwrap_or_default();
+            digits_of_exp(&(log * exp))?;
+        }
+        let exp = exp
+            .to_isize()
+            .ok_or_else(|| invalid("The value of the expression is too large"))?;
+        return Ok(pow(base, exp, places));
+    }
+    match base.is_positive() {
+        true => {
+            let extra = digits_of_exp(&(ln(base, places)? * exp))?;
+            exp_of(&(ln(base, places + extra)? * exp), places, extra)
+        }
+        false if base.is_zero() && exp.is_positive() => Ok(BigDecimal::zero()),
+        false => Err(invalid(
+            "Only positive values can be raised to fractional powers",
+        )),
+    }
+}
+
+/// The `n`th root of a value, where `n` is a positive integer. Odd roots of
+/// negative values are negative.
+fn root(value: &BigDecimal, n: &BigDecimal, places: i64) -> Result<BigDecimal, ConversionError> {
+    let n = Some(n)
+        .filter(|n| n.is_integer())
+        .and_then(|n| n.to_u32())
+        .filter(|&n| n > 0)
+        .ok_or_else(|| invalid("Roots must be positive integers"))?;
+    if value.is_negative() {
+        return match n % 2 {
+            1 => root(&-value, &BigDecimal::from(n), places).map(|r| -r),
+            _ => Err(invalid("Even roots of negative values aren't real")),
+        };
+    }
+    if value.is_zero() {
+        return Ok(BigDecimal::zero());
+    }
+    let significant_digits = (places + magnitude(value) / n as i64 + 2).max(1);
+    let context = Context::default()
+        .with_prec(significant_digits)
+        .unwrap_or_default();
+    match n {
+        1 => Ok(value.clone()),
+        2 => value
+            .sqrt_with_context(&context)
+            .ok_or_else(|| invalid("Even roots of negative values aren't real")),
+        3 => Ok(value.cbrt_with_context(&context)),
+        n => exp(
+            &div(&ln(value, places)?, &BigDecimal::from(n), places),
+            places,
+        ),
+    }
+}
+
+/// The natural logarithm, from the series
+/// `ln(x) = 2·(s + s³/3 + s⁵/5 + …)` with `s = (x - 1) / (x + 1)`.
+///
+/// Square roots bring `x` close to 1 first, so that the series converges
+/// quickly, and each one halves the logarithm.
+pub(super) fn ln(value: &BigDecimal, places: i64) -> Result<BigDecimal, ConversionError> {
+    if !value.is_positive() {
+        return Err(invalid("Logarithms are only defined for positive values"));
+    }
+    let places = places + GUARD_PLACES;
+    let close = BigDecimal::new(BigInt::one(), 2);
+    let mut x = value.clone();
+    let mut halvings = 0;
+    while (x.clone() - 1_u32).abs() > close {
+        x = root(&x, &BigDecimal::from(2), places)?;
+        halvings += 1;
+    }
+
+    let s = div(&(x.clone() - 1_u32), &(x + 1_u32), places);
+    let s_squared = (s.square()).round(places);
+    let epsilon = BigDecimal::new(BigInt::one(), places);
+    let mut term = s;
+    let mut sum = BigDecimal::zero();
+    let mut n = 1_u32;
+    while term.abs() > epsilon {
+        sum += div(&term, &BigDecimal::from(n), places);
+        term = (term * s_squared.clone()).round(places);
+        n += 2;
+    }
+    Ok(sum * 2_u32 * BigDecimal::from(BigInt::from(2).pow(halvings)))
+}
+
+/// The exponential function, from its Taylor series.
+fn exp(value: &BigDecimal, places: i64) -> Result<BigDecimal, ConversionError> {
+    let extra = digits_of_exp(value)?;
+    exp_of(value, places, extra)
+}
+
+/// The exponential of a value, whose result has `extra` digits before the
+/// radix point.
+///
+/// The value is halved until it's small, so that the series converges
+/// quickly, and the result is squared once for each halving.
+fn exp_of(value: &BigDecimal, places: i64, extra: i64) -> Result<BigDecimal, ConversionError> {
+    let small = BigDecimal::new(BigInt::one(), 2);
+    let mut x = value.clone();
+    let mut halvings = 0;
+    while x.abs() > small {
+        x = x.half();
+        halvings += 1;
+    }
+    let digits = places + extra + halvings / 3 + GUARD_PLACES;
+
+    let epsilon = BigDecimal::new(BigInt::one(), digits);
+    let mut term = BigDecimal::one();
+    let mut sum = BigDecimal::zero();
+    let mut n = 1_u32;
+    while term.abs() > epsilon {
+        sum += term.clone();
+        term = div(&(term * x.clone()), &BigDecimal::from(n), digits);
+        n += 1;
+    }
+    for _ in 0..halvings {
+        sum = sum.square().with_prec(digits.max(1) as u64);
+    }
+    Ok(sum.round(places))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str) -> Result<String, ConversionError> {
+        Expression::from_str(s).and_then(|e| e.eval(30).map(|v| v.to_string()))
+    }
+
+    fn ratio(numerator: i64, denominator: i64) -> BigRational {
+        BigRational::new(BigInt::from(numerator), BigInt::from(denominator))
+    }
+
+    #[test]
+    fn evaluates_arithmetic() {
+        assert_eq!(Ok("7".to_string()), eval("1 + 2 * 3"));
+        assert_eq!(Ok("9".to_string()), eval("(1 + 2) * 3"));
+        assert_eq!(Ok("512".to_string()), eval("2^3^2"));
+        assert_eq!(Ok("-4".to_string()), eval("-2^2"));
+        assert_eq!(Ok("0.25".to_string()), eval("2^-2"));
+        assert_eq!(
+            Ok("3.333333333333333333333333333333".to_string()),
+            eval("10/3")
+        );
+    }
+
+    #[test]
+    fn evaluates_functions_and_constants() {
+        assert_eq!(
+            Ok("2.645751311064590590501615753639".to_string()),
+            eval("sqrt(7)")
+        );
+        assert_eq!(
+            Ok("1.618033988749894848204586834366".to_string()),
+            eval("(1+sqrt(5))/2")
+        );
+        assert_eq!(
+            Ok("1.324717957244746025960908854478".to_string()),
+            eval("root(3, (9 + sqrt(69))/18) + root(3, (9 - sqrt(69))/18)")
+        );
+        assert_eq!(
+            Ok("7.389056098930650227230427460575".to_string()),
+            eval("exp(2)")
+        );
+        assert_eq!(
+            Ok("0.693147180559945309417232121458".to_string()),
+            eval("ln(2)")
+        );
+        assert_eq!(
+            Ok("9.869604401089358618834490999876".to_string()),
+            eval("pi^2")
+        );
+        assert_eq!(
+            Ok("1.259921049894873164767210607278".to_string()),
+            eval("2^(1/3)")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(eval("1 +").is_err());
+        assert!(eval("(1 + 2").is_err());
+        assert!(eval("foo(2)").is_err());
+        assert!(eval("root(2)").is_err());
+        assert!(eval("1/0").is_err());
+        assert!(eval("ln(-1)").is_err());
+        assert!(eval("sqrt(-2)").is_err());
+        assert!(eval("2^1000000").is_err());
+        let position = |s: &str| match eval(s) {
+            Err(ConversionError::MalformedExpression { position, .. }) => Some(position),
+            _ => None,
+        };
+        assert_eq!(Some(3), position("1 +"));
+        assert_eq!(Some(6), position("(1 + 2"));
+        assert_eq!(Some(0), position("foo(2)"));
+        assert_eq!(Some(2), position("1 $ 2"));
+        assert_eq!(Some(5), position("1 + 2)"));
+        assert_eq!(None, position("ln(0)"));
+    }
+
+    #[test]
+    fn rational_expressions_are_exact() {
+        let exact = |s: &str| Expression::from_str(s).unwrap().exact();
+        assert_eq!(Some(ratio(10, 3)), exact("10/3"));
+        assert_eq!(Some(ratio(-3, 2)), exact("-sqrt(9/4)"));
+        assert_eq!(Some(ratio(1, 256)), exact("hex^-2"));
+        assert_eq!(Some(ratio(2, 1)), exact("root(3, 8)"));
+        assert_eq!(None, exact("sqrt(2)"));
+        assert_eq!(None, exact("pi"));
+    }
+}
@@ -0,0 +1,177 @@
+use bigdecimal::{num_bigint::BigInt, num_traits::ToPrimitive, One, Signed, Zero};
+
+/// Integers with at most this many digits are converted a digit at a time.
+static BLOCK_DIGITS: usize = 32;
+
+/// Reciprocals of powers with at most this many bits are worked out with a
+/// single long division.
+static RECIPROCAL_BITS: u64 = 4096;
+
+/// The value of digits in an integer base, from the most significant down.
+///
+/// The digits are split in two, with the lower half as long as the largest
+/// power of 2 times `BLOCK_DIGITS` that fits, and the halves are converted
+/// the same way and joined with a power of the base. Every power used is a
+/// square of the one below it, so there are only as many as there are levels.
+pub(super) fn integer_from_digits(digits: &[i64], base: &BigInt) -> BigInt {
+    let mut powers = vec![base.pow(BLOCK_DIGITS as u32)];
+    while BLOCK_DIGITS << powers.len() < digits.len() {
+        let square = powers.last().unwrap().pow(2);
+        powers.push(square);
+    }
+    join_digits(digits, base, &powers)
+}
+
+fn join_digits(digits: &[i64], base: &BigInt, powers: &[BigInt]) -> BigInt {
+    if digits.len() <= BLOCK_DIGITS {
+        return digits
+            .iter()
+            .fold(BigInt::zero(), |sum, &d| sum * base + BigInt::from(d));
+    }
+    let level = (0..powers.len())
+        .rev()
+        .find(|&level| BLOCK_DIGITS << level < digits.len())
+        .unwrap();
+    let (high, low) = digits.split_at(digits.len() - (BLOCK_DIGITS << level));
+    join_digits(high, base, powers) * &powers[level] + join_digits(low, base, powers)
+}
+
+/// The digits of a nonnegative integer in an integer base greater than 1,
+/// from the most significant down.
+///
+/// This undoes `integer_from_digits`: the integer is split by the largest
+/// power of the base of the form `base^(BLOCK_DIGITS 2^k)` that's less than
+/// its square root, and the quotient and remainder are written the same way,
+/// with the remainder padded with zeros to fill its half. Each split divides
+/// by a power with a reciprocal worked out ahead of time, so the whole
+/// conversion takes a few multiplications at each level instead of a division
+/// for every digit.
+pub(super) fn integer_to_digits(value: &BigInt, base: &BigInt) -> Vec<i64> {
+    let mut powers = vec![Power::new(base.pow(BLOCK_DIGITS as u32))];
+    while value >= &powers.last().unwrap().value {
+        let square = Power::new(powers.last().unwrap().value.pow(2));
+        powers.push(square);
+    }
+    powers.pop();
+    let mut digits = Vec::new();
+    split_digits(value, base, &powers, false, &mut digits);
+    digits
+}
+
+/// A power of the base, along with what's needed to divide by it quickly.
+struct Power {
+    value: BigInt,
+    bits: u64,
+    reciprocal: BigInt,
+}
+
+impl Power {
+    fn new(value: BigInt) -> Self {
+        Self {
+            bits: value.bits(),
+            reciprocal: reciprocal(&value),
+            value,
+        }
+    }
+
+    /// The quotient and remainder of an integer less than the square of the
+    /// power, by Barrett reduction. The estimated quotient is at most 2 less
+    /// than the true one.
+    fn div_rem(&self, num: &BigInt) -> (BigInt, BigInt) {
+        let mut quotient = ((num >> (self.bits - 1)) * &self.reciprocal) >> (self.bits + 1);
+        let mut remainder = num - &quotient * &self.value;
+        while remainder >= self.value {
+            quotient += 1;
+            remainder -= &self.value;
+        }
+        (quotient, remainder)
+    }
+}
+
+fn split_digits(value: &BigInt, base: &BigInt, powers: &[Power], pad: bool, out: &mut Vec<i64>) {
+    let Some((power, lower)) = powers.split_last() else {
+        let mut block = Vec::new();
+        let mut rest = value.clone();
+        while !rest.is_zero() || (block.is_empty() && !pad) {
+            block.push((&rest % base).to_i64().unwrap());
+            rest /= base;
+        }
+        if pad {
+            block.resize(BLOCK_DIGITS, 0);
+        }
+        out.extend(block.into_iter().rev());
+        return;
+    };
+    let (quotient, remainder) = power.div_rem(value);
+    if quotient.is_zero() && !pad {
+        return split_digits(&remainder, base, lower, false, out);
+    }
+    split_digits(&quotient, base, lower, pad, out);
+    split_digits(&remainder, base, lower, true, out);
+}
+
+/// `⌊4ⁿ / p⌋`, where `p` has `n` bits.
+///
+/// This is the reciprocal of the top half of `p`, shifted into place and
+/// refined with one step of Newton's method, which doubles the number of
+/// correct bits, then corrected by the few units it can still be off by.
+fn reciprocal(p: &BigInt) -> BigInt {
+    let bits = p.bits();
+    let scale = BigInt::one() << (2 * bits);
+    if bits <= RECIPROCAL_BITS {
+        return scale / p;
+    }
+    let shift = bits - (bits / 2 + 1);
+    let estimate = reciprocal(&(p >> shift)) << shift;
+    let error = &scale - p * &estimate;
+    let mut reciprocal = ((&estimate * error) >> (2 * bits)) + estimate;
+    let mut remainder = scale - p * &reciprocal;
+    while remainder.is_negative() {
+        reciprocal -= 1;
+        remainder += p;
+    }
+    while &remainder >= p {
+        reciprocal += 1;
+        remainder -= p;
+    }
+    reciprocal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_integers_in_blocks() {
+        let ten = BigInt::from(10);
+        let digits: Vec<i64> = (0..1000).map(|i| (i * 7 + 3) % 10).collect();
+        let value = integer_from_digits(&digits, &ten);
+        let decimal: String = digits.iter().map(|d| d.to_string()).collect();
+        assert_eq!(decimal, value.to_string());
+        assert_eq!(digits, integer_to_digits(&value, &ten));
+
+        let power = BigInt::from(10).pow(BLOCK_DIGITS as u32 * 4);
+        let mut expected = vec![1];
+        expected.resize(BLOCK_DIGITS * 4 + 1, 0);
+        assert_eq!(expected, integer_to_digits(&power, &ten));
+        assert_eq!(vec![0], integer_to_digits(&BigInt::zero(), &ten));
+    }
+
+    #[test]
+    fn finds_reciprocals_of_large_powers() {
+        let p = BigInt::from(3).pow(20_000);
+        let scale = BigInt::one() << (2 * p.bits());
+        assert_eq!(&scale / &p, reciprocal(&p));
+    }
+
+    #[test]
+    fn converts_large_integers_between_bases() {
+        let ones = vec![1; 100_000];
+        let value = integer_from_digits(&ones, &BigInt::from(2));
+        assert_eq!((BigInt::one() << 100_000) - 1, value);
+        let decimal = integer_to_digits(&value, &BigInt::from(10));
+        assert_eq!(value.to_string().len(), decimal.len());
+        assert_eq!(value, integer_from_digits(&decimal, &BigInt::from(10)));
+        assert_eq!(ones, integer_to_digits(&value, &BigInt::from(2)));
+    }
+}
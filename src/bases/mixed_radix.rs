@@ -0,0 +1,378 @@
+use std::{fmt, str::FromStr};
+
+use bigdecimal::{num_bigint::BigInt, BigDecimal, One, Zero};
+
+use super::{div, floor, split_sign, working_places, ConversionError};
+
+/// A numeral system where each position has its own radix. Mixed-radix
+/// representations are written as base-10 groups separated by `:`, like
+/// `1:23:45.6`.
+#[derive(PartialEq, Clone, Debug)]
+pub enum MixedRadix {
+    /// Radices listed from the most significant position down, like `24:60:60`.
+    /// One more unbounded position sits above them, and fractions are decimal.
+    List(Vec<u64>),
+    /// The factorial number system, where position `i` has radix `i + 1` and
+    /// fractional position `-n` has a place value of `1/n!`
+    Factorial,
+    /// The primorial number system, where position `i` has the `(i + 1)`th
+    /// prime as its radix
+    Primorial,
+}
+
+impl FromStr for MixedRadix {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "time" | "hms" | "dms" => Ok(Self::List(vec![60, 60])),
+            "dhms" => Ok(Self::List(vec![24, 60, 60])),
+            "factorial" | "factoradic" => Ok(Self::Factorial),
+            "primorial" => Ok(Self::Primorial),
+            list if list.contains(':') => list
+                .split(':')
+                .map(|r| match r.trim().parse() {
+                    Ok(r) if r >= 2 => Ok(r),
+                    _ => Err(ConversionError::InvalidMixedRadix(format!(
+                        "Invalid radix `{r}`; radices must be integers above 1"
+                    ))),
+                })
+                .collect::<Result<_, _>>()
+                .map(Self::List),
+            _ => Err(ConversionError::InvalidMixedRadix(format!(
+                "Unrecognized mixed radix: {s}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for MixedRadix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::List(radices) => write!(
+                f,
+                "{}",
+                radices
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect::<Vec<_>>()
+                    .join(":")
+            ),
+            Self::Factorial => write!(f, "factorial"),
+            Self::Primorial => write!(f, "primorial"),
+        }
+    }
+}
+
+fn nth_prime(n: usize) -> u64 {
+    (2..)
+        .filter(|&p: &u64| (2..).take_while(|d| d * d <= p).all(|d| p % d != 0))
+        .nth(n)
+        .unwrap()
+}
+
+/// A fraction this close to zero, or a digit this close to the next integer,
+/// is treated as exact when values are worked out to the given decimal places.
+fn tolerance(places: i64) -> BigDecimal {
+    BigDecimal::new(BigInt::one(), places - 10)
+}
+
+impl MixedRadix {
+    /// The number of possible digits at an integer position, or `None` if
+    /// the position is unbounded.
+    fn radix(&self, position: usize) -> Option<u64> {
+        match self {
+            Self::List(radices) => radices
+                .len()
+                .checked_sub(position + 1)
+                .map(|idx| radices[idx]),
+            Self::Factorial => Some(position as u64 + 1),
+            Self::Primorial => Some(nth_prime(position)),
+        }
+    }
+
+    /// How many times smaller the place value of fractional position `-n` is
+    /// than the place value of the position before it.
+    fn fractional_radix(&self, n: usize) -> u64 {
+        match self {
+            Self::List(_) => 10,
+            Self::Factorial => n as u64,
+            Self::Primorial => nth_prime(n - 1),
+        }
+    }
+
+    fn max_positions(&self) -> Option<usize> {
+        match self {
+            Self::List(radices) => Some(radices.len() + 1),
+            Self::Factorial | Self::Primorial => None,
+        }
+    }
+
+    /// The place value of the digit with the given exponent, rounded to the
+    /// given number of decimal places.
+    pub fn weight(&self, exp: isize, places: i64) -> BigDecimal {
+        match exp >= 0 {
+            true => BigDecimal::from(
+                (0..exp as usize)
+                    .map(|p| BigInt::from(self.radix(p).unwrap_or(1)))
+                    .product::<BigInt>(),
+            ),
+            false => div(
+                &BigDecimal::one(),
+                &BigDecimal::from(
+                    (1..=exp.unsigned_abs())
+                        .map(|n| BigInt::from(self.fractional_radix(n)))
+                        .product::<BigInt>(),
+                ),
+                places,
+            ),
+        }
+    }
+
+    /// Split a representation into its digits, each paired with its exponent.
+    pub fn digit_exponent_pairs(&self, rep: &str) -> Vec<(String, isize)> {
+        let (_, rep) = split_sign(rep);
+        let (integer, fraction) = rep.split_once('.').unwrap_or((rep, ""));
+        let groups: Vec<_> = integer.split(':').collect();
+        let fractional_groups: Vec<_> = match self {
+            Self::List(_) => fraction.chars().map(|c| c.to_string()).collect(),
+            _ if fraction.is_empty() => vec![],
+            _ => fraction.split(':').map(|g| g.to_string()).collect(),
+        };
+        let top = groups.len() as isize - 1;
+        groups
+            .into_iter()
+            .map(|g| g.trim_end_matches('…').to_string())
+            .zip((0..).map(|i| top - i))
+            .chain(
+                fractional_groups
+                    .into_iter()
+                    .map(|g| g.trim_end_matches('…').to_string())
+                    .zip((1..).map(|n: isize| -n)),
+            )
+            .filter(|(g, _)| !g.is_empty())
+            .collect()
+    }
+
+    /// Parse a mixed-radix representation, like `1:23:45.6`.
+    pub fn val_from(&self, input: &str, places: i64) -> Result<BigDecimal, ConversionError> {
+        let (is_negative, input) = split_sign(input);
+        let (integer, fraction) = match input.split('.').collect::<Vec<_>>()[..] {
+            [integer] => (integer, ""),
+            [integer, fraction] => (integer, fraction),
+            _ => return Err(ConversionError::TooManyRadixPoints { position: None }),
+        };
+        let parse_group = |group: &str, radix: Option<u64>| -> Result<BigInt, ConversionError> {
+            let digit = BigInt::from_str(group.trim())
+                .ok()
+                .filter(|d| d >= &BigInt::zero())
+                .ok_or_else(|| ConversionError::UnrecognizedDigit {
+                    digit: group.to_string(),
+                    position: None,
+                })?;
+            match radix {
+                Some(r) if digit >= BigInt::from(r) => Err(ConversionError::InvalidDigit {
+                    digit: group.to_string(),
+                    base: format!("radix {r}"),
+                    position: None,
+                }),
+                _ => Ok(digit),
+            }
+        };
+
+        let groups: Vec<_> = integer.split(':').collect();
+        if self.max_positions().is_some_and(|max| groups.len() > max) {
+            return Err(ConversionError::malformed(format!(
+                "Too many `:` groups for the radices {self}"
+            )));
+        }
+        let integer = match integer {
+            "" => BigInt::zero(),
+            _ => {
+                groups
+                    .iter()
+                    .rev()
+                    .enumerate()
+                    .try_fold(
+                        (BigInt::zero(), BigInt::one()),
+                        |(sum, weight), (position, group)| {
+                            let radix = self.radix(position);
+                            parse_group(group, radix)
+                                .map(|d| (sum + d * weight.clone(), weight * radix.unwrap_or(1)))
+                        },
+                    )?
+                    .0
+            }
+        };
+
+        let fraction = match self {
+            Self::List(_) if fraction.chars().all(|c| c.is_ascii_digit()) => {
+                BigDecimal::from_str(&format!("0.{fraction}0")).unwrap()
+            }
+            Self::List(_) => {
+                return Err(ConversionError::malformed(format!(
+                    "Unrecognized fraction in input: {fraction}"
+                )))
+            }
+            _ if fraction.is_empty() => BigDecimal::zero(),
+            _ => fraction.split(':').enumerate().try_fold(
+                BigDecimal::zero(),
+                |sum, (idx, group)| {
+                    parse_group(group, Some(self.fractional_radix(idx + 1))).map(|d| {
+                        sum + BigDecimal::from(d) * self.weight(-(idx as isize) - 1, places)
+                    })
+                },
+            )?,
+        };
+
+        let magnitude = BigDecimal::from(integer) + fraction;
+        match is_negative {
+            true => Ok(-magnitude),
+            false => Ok(magnitude),
+        }
+    }
+
+    /// Write a value in this mixed radix, with at most `-precision - 1`
+    /// fractional digits.
+    pub fn val_to(&self, value: &BigDecimal, precision: isize) -> String {
+        let tolerance = tolerance(working_places(precision, &BigDecimal::from(10)));
+        let magnitude = value.abs();
+        let integer_part = floor(&magnitude);
+        let mut fraction = magnitude - integer_part.clone();
+        let mut n = integer_part.into_bigint_and_exponent().0;
+
+        let mut groups = Vec::new();
+        for position in 0.. {
+            match self.radix(position) {
+                Some(radix) => {
+                    let width = (radix - 1).to_string().len();
+                    groups.push(format!("{:0>width$}", n.clone() % radix));
+                    n /= radix;
+                }
+                None => {
+                    groups.push(n.to_string());
+                    break;
+                }
+            }
+            if n.is_zero() {
+                break;
+            }
+        }
+        if let Some(leading) = groups.last_mut() {
+            *leading = leading.trim_start_matches('0').to_string();
+            if leading.is_empty() {
+                leading.push('0');
+            }
+        }
+        groups.reverse();
+
+        let mut fractional_groups = Vec::new();
+        for n in 1..=-(precision + 1) as usize {
+            if fraction <= tolerance {
+                break;
+            }
+            let scaled = fraction * BigDecimal::from(self.fractional_radix(n));
+            let digit = floor(&(scaled.clone() + tolerance.clone()));
+            fraction = (scaled - digit.clone()).max(BigDecimal::zero());
+            fractional_groups.push(digit.to_string());
+        }
+        let separator = match self {
+            Self::List(_) => "",
+            _ => ":",
+        };
+
+        let mut output = String::from("");
+        if value < &BigDecimal::zero() {
+            output.push('-');
+        }
+        output.push_str(&groups.join(":"));
+        if !fractional_groups.is_empty() {
+            output.push('.');
+            output.push_str(&fractional_groups.join(separator));
+        }
+        if fraction > tolerance {
+            output.push('…'); // ellide
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn val(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn parses_radix_lists_and_names() {
+        assert_eq!(Ok(MixedRadix::List(vec![24, 60, 60])), "24:60:60".parse());
+        assert_eq!(Ok(MixedRadix::List(vec![60, 60])), "time".parse());
+        assert_eq!(Ok(MixedRadix::Factorial), "factoradic".parse());
+        assert!("10".parse::<MixedRadix>().is_err());
+        assert!("60:1".parse::<MixedRadix>().is_err());
+    }
+
+    #[test]
+    fn parses_time() {
+        let time = MixedRadix::List(vec![60, 60]);
+        assert_eq!(Ok(val("5025.6")), time.val_from("1:23:45.6", 50));
+        assert_eq!(Ok(val("-90")), time.val_from("-1:30", 50));
+        assert!(time.val_from("1:60", 50).is_err());
+        assert!(time.val_from("1:2:3:4", 50).is_err());
+    }
+
+    #[test]
+    fn writes_time() {
+        let time = MixedRadix::List(vec![60, 60]);
+        assert_eq!("1:23:45.6", time.val_to(&val("5025.6"), -9));
+        assert_eq!("1:05:00", time.val_to(&val("3900"), -9));
+        assert_eq!("100:00:00", time.val_to(&val("360000"), -9));
+        assert_eq!("0", time.val_to(&val("0"), -9));
+    }
+
+    #[test]
+    fn round_trips_factoradic() {
+        let factorial = MixedRadix::Factorial;
+        assert_eq!("3:4:1:0:1:0", factorial.val_to(&val("463"), -9));
+        assert_eq!(Ok(val("463")), factorial.val_from("3:4:1:0:1:0", 50));
+    }
+
+    #[test]
+    fn writes_finite_factorial_fractions() {
+        let factorial = MixedRadix::Factorial;
+        let third = BigDecimal::one() / BigDecimal::from(3);
+        assert_eq!("0.0:0:2", factorial.val_to(&third.round(50), -9));
+        assert_eq!("0.0:1:1", factorial.val_to(&(third * 2_u32).round(50), -9));
+    }
+
+    #[test]
+    fn writes_primorial() {
+        // 2·30 + 1·6 + 1·2 + 1·1
+        assert_eq!("2:1:1:1", MixedRadix::Primorial.val_to(&val("69"), -9));
+        assert_eq!(Ok(val("69")), MixedRadix::Primorial.val_from("2:1:1:1", 50));
+    }
+
+    #[test]
+    fn weights_of_positions() {
+        let time = MixedRadix::List(vec![60, 60]);
+        assert_eq!(val("3600"), time.weight(2, 50));
+        assert_eq!(val("0.1"), time.weight(-1, 50));
+        assert_eq!(val("6"), MixedRadix::Factorial.weight(3, 50));
+    }
+
+    #[test]
+    fn splits_groups_into_pairs() {
+        let pairs = MixedRadix::List(vec![60, 60]).digit_exponent_pairs("1:23:45.6");
+        assert_eq!(
+            vec![
+                ("1".to_string(), 2),
+                ("23".to_string(), 1),
+                ("45".to_string(), 0),
+                ("6".to_string(), -1)
+            ],
+            pairs
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use std::cmp::Ordering;
+
+use bigdecimal::{num_bigint::BigInt, num_traits::Euclid, BigDecimal, One, ToPrimitive, Zero};
+
+use super::{
+    ceil, digit_bound, digit_value, parse_digit, pow, rep_to_digit_exponent_pairs, split_complex,
+    split_sign, val_to_digits, val_to_expansion, Complex, ConversionError, DigitSet, Digits,
+    GUARD_PLACES,
+};
+
+/// How the digits of an input compare with the greedy expansion of its value,
+/// which is the canonical way to write it.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Normalization {
+    /// Whether the digits satisfy the Parry condition, which makes them the
+    /// greedy expansion already
+    pub is_admissible: bool,
+    /// The digits after each carry, starting with the input and ending with
+    /// the canonical form
+    pub steps: Vec<String>,
+}
+
+impl Normalization {
+    /// The greedy expansion of the value, which is how the output would write
+    /// it in the same base.
+    pub fn canonical(&self) -> &str {
+        self.steps.last().map(|s| s.as_str()).unwrap_or("")
+    }
+}
+
+/// The fractional digits of the quasi-greedy expansion of 1, to `len` digits.
+fn expansion_of_one(base: &BigDecimal, len: usize) -> Option<Vec<i64>> {
+    let precision = -(len as isize) - 1;
+    let one = val_to_expansion(&BigDecimal::one(), base, &DigitSet::QuasiGreedy, precision);
+    Some(
+        one.ok()?
+            .pairs
+            .iter()
+            .filter(|&&(_, exp)| exp < 0)
+            .map(|&(digit, _)| digit)
+            .collect(),
+    )
+}
+
+/// Whether digits, listed from the most significant down, satisfy the Parry
+/// condition: the digits from each position on must come before the
+/// quasi-greedy expansion of 1 in lexicographic order.
+///
+/// In an integer base that's every string of digits in range. Otherwise the
+/// expansion of 1 is only worked out as far as the comparisons need it,
+/// doubling its length whenever one runs past the end, since most stop at the
+/// first few digits.
+fn is_admissible(digits: &[i64], base: &BigDecimal) -> bool {
+    let largest_digit = ceil(base).to_i64().map_or(i64::MAX, |c| c - 1);
+    if !digits.iter().all(|&d| (0..=largest_digit).contains(&d)) {
+        return false;
+    }
+    if base.is_integer() {
+        return true;
+    }
+    let mut one = Vec::new();
+    for k in 0..digits.len() {
+        let tail = &digits[k..];
+        loop {
+            let compared = tail.len().min(one.len());
+            match tail[..compared].cmp(&one[..compared]) {
+                Ordering::Less => break,
+                Ordering::Greater => return false,
+                Ordering::Equal if compared == tail.len() => break,
+                Ordering::Equal => {
+                    let len = (2 * one.len()).max(32).min(digits.len());
+                    match expansion_of_one(base, len) {
+                        Some(longer) if longer.len() > one.len() => one = longer,
+                        _ => return false,
+                    }
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Check whether a string of digits in a base greater than 1 is the greedy
+/// expansion of its value, and rewrite it into that form if not.
+///
+/// The digits are greedy as long as the digits at and below each position add
+/// up to less than the place value of the position above. Wherever they don't,
+/// a `1` is carried into the position above, and the rest is written greedily
+/// below it. Carrying from the lowest such position up shows each step.
+pub(super) fn normalize(
+    input: &str,
+    base: &BigDecimal,
+    places: i64,
+) -> Result<Normalization, ConversionError> {
+    if base <= &BigDecimal::one() {
+        return Err(ConversionError::unsupported(
+            "Only bases greater than 1 have a greedy expansion",
+        ));
+    }
+    let (is_negative, unsigned) = split_sign(input);
+    if unsigned.contains(['/', '(', '^', '*']) {
+        return Err(ConversionError::unsupported(
+            "Only plain digits can be normalized",
+        ));
+    }
+    let complex_base = Complex::from(base.clone());
+    let mut positions = rep_to_digit_exponent_pairs(unsigned)
+        .into_iter()
+        .map(|(char, exp)| parse_digit(&char, &complex_base).map(|d| (d, exp)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let digits: Vec<_> = positions.iter().map(|&(d, _)| d).collect();
+    let is_admissible = is_admissible(&digits, base);
+
+    let show = |positions: &[(i64, isize)], is_elided: bool| {
+        Digits {
+            is_negative,
+            ..Digits::from_positions(positions.to_vec(), is_elided)
+        }
+        .to_string()
+    };
+    let mut steps = vec![show(&positions, false)];
+    if is_admissible {
+        return Ok(Normalization {
+            is_admissible,
+            steps,
+        });
+    }
+
+    // Greedy digits can need more places than the input had
+    let bottom = positions.iter().map(|&(_, e)| e).min().unwrap_or(0);
+    let precision = bottom - positions.len() as isize - 1;
+    let accuracy = places - GUARD_PLACES;
+    for _ in 0..2 * positions.len() + 2 {
+        positions.sort_by_key(|&(_, exp)| exp);
+        let mut tail = BigDecimal::zero();
+        let carry = positions.iter().find_map(|&(digit, exp)| {
+            tail += BigDecimal::from(digit) * pow(base, exp, places);
+            let above = pow(base, exp + 1, places);
+            (tail.round(accuracy) >= above.round(accuracy))
+                .then(|| (exp, (tail.clone() - above).round(accuracy)))
+        });
+        let Some((exp, remaining)) = carry else {
+            break;
+        };
+        let greedy = val_to_digits(&remaining.max(BigDecimal::zero()), base, precision)?;
+        positions.retain(|&(_, e)| e > exp);
+        match positions.iter_mut().find(|(_, e)| *e == exp + 1) {
+            Some((digit, _)) => *digit += 1,
+            None => positions.push((1, exp + 1)),
+        }
+        positions.extend(greedy.pairs.iter().filter(|&&(d, _)| d != 0));
+        positions.sort_by_key(|&(_, exp)| -exp);
+        steps.push(show(&positions, greedy.is_elided));
+    }
+
+    Ok(Normalization {
+        is_admissible,
+        steps,
+    })
+}
+
+/// Carry any digits past the end of an integer base into the positions above
+/// them, like `1[12]` to `22` in base 10, or return `None` when every digit is
+/// already in range.
+///
+/// Every position keeps the remainder of its digit modulo the base, and the
+/// rest is carried up, so the value doesn't change. This also rewrites any
+/// negative digits with the standard ones.
+pub(super) fn carry(input: &str, base: &Complex) -> Result<Option<String>, ConversionError> {
+    let k = match base.is_real() && base.re.is_integer() {
+        true => base.re.to_i64().filter(|k| k.abs() >= 2),
+        false => None,
+    }
+    .ok_or_else(|| ConversionError::unsupported("Only integer bases can carry digits"))?;
+    let (is_negative, unsigned) = split_sign(input);
+    if split_complex(input).is_some() || unsigned.contains(['/', '(', '^', '*', '×']) {
+        return Err(ConversionError::unsupported(
+            "Only plain real digits can be carried",
+        ));
+    }
+    let positions = rep_to_digit_exponent_pairs(unsigned)
+        .into_iter()
+        .map(|(char, exp)| digit_value(&char, base).map(|d| (d, exp)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let bound = digit_bound(base);
+    if positions
+        .iter()
+        .all(|&(d, _)| BigDecimal::from(d.abs()) < bound)
+    {
+        return Ok(None);
+    }
+
+    let bottom = positions.iter().map(|&(_, e)| e).min().unwrap_or(0);
+    let top = positions.iter().map(|&(_, e)| e).max().unwrap_or(0);
+    let (modulus, k) = (BigInt::from(k.abs()), BigInt::from(k));
+    let mut carried = Vec::new();
+    let mut carry = BigInt::zero();
+    let mut exp = bottom;
+    while exp <= top || !carry.is_zero() {
+        let total = carry
+            + positions
+                .iter()
+                .filter(|&&(_, e)| e == exp)
+                .map(|&(d, _)| d)
+                .sum::<i64>();
+        let digit = total.rem_euclid(&modulus);
+        carry = (total - &digit) / &k;
+        carried.push((digit.to_i64().unwrap(), exp));
+        exp += 1;
+    }
+    Ok(Some(
+        Digits {
+            is_negative,
+            ..Digits::from_positions(carried, false)
+        }
+        .to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bases::constant;
+
+    fn phi() -> BigDecimal {
+        constant("phi", 60).unwrap()
+    }
+
+    #[test]
+    fn checks_the_parry_condition() {
+        assert!(is_admissible(&[1, 0, 0], &phi()));
+        assert!(is_admissible(&[1, 0, 1, 0, 1], &phi()));
+        assert!(!is_admissible(&[1, 1], &phi()));
+        assert!(!is_admissible(&[1, 0, 1, 1], &phi()));
+        assert!(is_admissible(&[9, 9, 9], &BigDecimal::from(10)));
+        assert!(is_admissible(&[2, 0, 2], &constant("silver", 60).unwrap()));
+        assert!(!is_admissible(&[2, 1], &constant("silver", 60).unwrap()));
+        // Only as much of the expansion of 1 as the digits need is worked out
+        let long: Vec<_> = (0..3000).map(|k| (k % 3 == 0) as i64).collect();
+        assert!(is_admissible(&long, &phi()));
+        assert!(is_admissible(&vec![9; 1_000_000], &BigDecimal::from(10)));
+    }
+
+    #[test]
+    fn normalizes_with_carries() {
+        let normalization = normalize("11", &phi(), 60).unwrap();
+        assert!(!normalization.is_admissible);
+        assert_eq!(vec!["11", "100"], normalization.steps);
+
+        let normalization = normalize("0111", &phi(), 60).unwrap();
+        assert_eq!(vec!["111", "200", "1001"], normalization.steps);
+        assert_eq!("1001", normalization.canonical());
+
+        let normalization = normalize("-1.11", &phi(), 60).unwrap();
+        assert_eq!(vec!["-1.11", "-2", "-10.01"], normalization.steps);
+    }
+
+    #[test]
+    fn carries_digits_past_the_base() {
+        let ten = Complex::from(BigDecimal::from(10));
+        assert_eq!(Ok(Some("22".to_string())), carry("1[12]", &ten));
+        assert_eq!(Ok(Some("-10.2".to_string())), carry("-[9].[12]", &ten));
+        assert_eq!(Ok(Some("119".to_string())), carry("[12]T", &ten));
+        assert_eq!(Ok(None), carry("123.45", &ten));
+        let negabinary = Complex::from(BigDecimal::from(-2));
+        assert_eq!(Ok(Some("110".to_string())), carry("2", &negabinary));
+        assert!(carry("[12]", &Complex::from(phi())).is_err());
+    }
+
+    #[test]
+    fn leaves_greedy_digits_alone() {
+        let normalization = normalize("10.01", &phi(), 60).unwrap();
+        assert!(normalization.is_admissible);
+        assert_eq!(vec!["10.01"], normalization.steps);
+        assert!(normalize("1/2", &phi(), 60).is_err());
+    }
+}
@@ -0,0 +1,404 @@
+use bigdecimal::{num_bigint::BigInt, num_traits::ToPrimitive, BigDecimal, One, Signed, Zero};
+use num_rational::BigRational;
+
+use super::{
+    ceil, fixed_point_bits,
+    rounding::{fixed_with_error, mul_with_error, pow_with_error},
+    to_fixed, working_places, Base, BaseConversion, ConversionError, DigitSet, GUARD_PLACES,
+    MAX_EXTRA_ATTEMPTS,
+};
+
+/// Digits are worked out to enough places for at least this many more digits
+/// whenever they run out of precision.
+static LOOKAHEAD_DIGITS: isize = 64;
+
+/// The digits of the output, from the leading digit down, each with its
+/// exponent. There's no fixed number of digits: each one is worked out when
+/// it's asked for, so a caller can stop wherever it likes.
+///
+/// The digits are the greedy ones the output is written with, starting from
+/// the digit of `b⁰` for values less than 1. They end once the value has been
+/// written exactly, which for most values in most bases is never, or with an
+/// error once a digit can't be worked out.
+///
+/// The digits are of the magnitude of the value, so a negative value has the
+/// same digits as its absolute value, and `is_negative` gives its sign.
+pub struct OutputDigits {
+    stream: Stream,
+    is_negative: bool,
+}
+
+enum Stream {
+    /// A rational value in a rational base, as the part of the value left
+    /// over in units of the place value of the next digit
+    Exact {
+        remainder: BigRational,
+        base: BigRational,
+        exp: isize,
+    },
+    Approximate(Box<Approximation>),
+    /// A stream that gave an error, and has no more digits
+    Failed,
+}
+
+/// The part of the value left over in units of the place value of the next
+/// digit, in fixed point, along with a bound on its error.
+///
+/// A digit is only given once the error can't change it. Otherwise the value
+/// and the base are worked out again to more places, and the digits given so
+/// far are taken back out. A digit that's still uncertain after
+/// `MAX_EXTRA_ATTEMPTS` more attempts ends the digits if the value is within
+/// its error of ending there, and is an error otherwise.
+struct Approximation {
+    conversion: BaseConversion,
+    places: i64,
+    bits: usize,
+    base: BigDecimal,
+    fixed_base: (BigInt, BigInt),
+    remainder: BigInt,
+    error: BigInt,
+    largest_digit: i64,
+    top: isize,
+    exp: isize,
+    given: Vec<i64>,
+    is_negative: bool,
+}
+
+impl OutputDigits {
+    pub(super) fn new(conversion: &BaseConversion) -> Result<Self, ConversionError> {
+        let is_real_base = matches!(
+            &conversion.output_base,
+            Base::Positional(base) if base.is_real() && base.re > BigDecimal::one()
+        );
+        if !is_real_base || conversion.output_digit_set != DigitSet::Standard {
+            return Err(ConversionError::unsupported(
+                "Only real bases greater than 1 with the standard digits can list their digits",
+            ));
+        }
+        let exact = BaseConversion::exact_base(
+            &conversion.output_base_string,
+            &conversion.output_digit_set,
+        )
+        .zip(conversion.exact_value());
+        if let Some((base, value)) = exact {
+            return Ok(Self {
+                stream: Stream::exact(&value.abs(), base),
+                is_negative: value.is_negative(),
+            });
+        }
+        let mut approximation = Approximation {
+            conversion: conversion.clone(),
+            places: 0,
+            bits: 0,
+            base: BigDecimal::zero(),
+            fixed_base: (BigInt::zero(), BigInt::zero()),
+            remainder: BigInt::zero(),
+            error: BigInt::zero(),
+            largest_digit: 0,
+            top: 0,
+            exp: 0,
+            given: Vec::new(),
+            is_negative: false,
+        };
+        approximation.start(conversion.working_places())?;
+        Ok(Self {
+            is_negative: approximation.is_negative,
+            stream: Stream::Approximate(Box::new(approximation)),
+        })
+    }
+
+    /// Whether the value is negative, since its digits are of its magnitude.
+    pub fn is_negative(&self) -> bool {
+        self.is_negative
+    }
+}
+
+impl Stream {
+    fn exact(value: &BigRational, base: BigRational) -> Self {
+        let mut exp = 0;
+        let mut power = BigRational::one();
+        while power.clone() * &base <= *value {
+            exp += 1;
+            power *= &base;
+        }
+        Self::Exact {
+            remainder: value / power,
+            base,
+            exp,
+        }
+    }
+}
+
+impl Iterator for OutputDigits {
+    type Item = Result<(i64, isize), ConversionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = match &mut self.stream {
+            Stream::Exact {
+                remainder,
+                base,
+                exp,
+            } => {
+                if remainder.is_zero() && *exp < 0 {
+                    return None;
+                }
+                let digit = remainder.floor();
+                *remainder = (remainder.clone() - &digit) * &*base;
+                *exp -= 1;
+                match digit.to_integer().to_i64() {
+                    Some(digit) => Ok((digit, *exp + 1)),
+                    None => Err(ConversionError::unsupported(
+                        "The base is too large to list its digits",
+                    )),
+                }
+            }
+            Stream::Approximate(approximation) => approximation.next_digit()?,
+            Stream::Failed => return None,
+        };
+        if next.is_err() {
+            self.stream = Stream::Failed;
+        }
+        Some(next)
+    }
+}
+
+impl Approximation {
+    /// Work out the value and the base to the given number of places, and
+    /// take the digits given so far back out.
+    fn start(&mut self, places: i64) -> Result<(), ConversionError> {
+        let conversion = self.conversion.at_places(places);
+        let value = conversion
+            .value_to_places(places)
+            .map_err(|e| ConversionError::InvalidInput(Box::new(e)))?;
+        let base = match &conversion.output_base {
+            Base::Positional(base) if base.is_real() && value.is_real() => base.re.clone(),
+            _ => {
+                return Err(ConversionError::unsupported(
+                    "Only real values can list their digits",
+                ))
+            }
+        };
+        let (value_error, base_error) = conversion.errors_at(places);
+        self.is_negative = value.re.is_negative();
+        let magnitude = value.re.abs();
+        if self.given.is_empty() {
+            // A value within its error of a place value has that digit
+            let bound = &magnitude + &value_error;
+            let mut power = base.clone();
+            self.top = 0;
+            while power <= bound {
+                self.top += 1;
+                power = (power * &base).round(places);
+            }
+        }
+        let scale = pow_with_error(&base, &base_error, -self.top, places);
+        let scaled = mul_with_error((&magnitude, &value_error), (&scale.0, &scale.1), places);
+
+        self.places = places;
+        self.bits = fixed_point_bits(places);
+        self.largest_digit = to_fixed(&ceil(&base), 0).to_i64().unwrap_or(i64::MAX) - 1;
+        self.fixed_base = fixed_with_error((base.clone(), base_error), self.bits);
+        self.base = base;
+        (self.remainder, self.error) = fixed_with_error(scaled, self.bits);
+        self.exp = self.top;
+        for digit in std::mem::take(&mut self.given) {
+            self.give(digit);
+        }
+        Ok(())
+    }
+
+    fn next_digit(&mut self) -> Option<Result<(i64, isize), ConversionError>> {
+        if self.remainder.is_zero() && self.error.is_zero() && self.exp < 0 {
+            return None;
+        }
+        let mut attempt = 0;
+        loop {
+            let one = BigInt::one() << self.bits;
+            let digit = (&self.remainder >> self.bits)
+                .to_i64()
+                .unwrap_or(0)
+                .clamp(0, self.largest_digit);
+            let fraction = &self.remainder - (BigInt::from(digit) << self.bits);
+            if fraction >= self.error && &fraction + &self.error < one {
+                return Some(Ok(self.give(digit)));
+            }
+            if attempt == MAX_EXTRA_ATTEMPTS {
+                return match self.ending_digit() {
+                    // Nothing is left, and the digits have reached the point
+                    Some(0) if self.exp < 0 => {
+                        self.end();
+                        None
+                    }
+                    Some(digit) => {
+                        let given = self.give(digit);
+                        self.end();
+                        Some(Ok(given))
+                    }
+                    None => Some(Err(ConversionError::UncertainDigit { exp: self.exp })),
+                };
+            }
+            let lookahead = (self.top - self.exp).max(LOOKAHEAD_DIGITS);
+            let places = working_places(self.exp - lookahead, &self.base).max(self.places)
+                + (GUARD_PLACES << attempt);
+            if let Err(error) = self.start(places) {
+                return Some(Err(error));
+            }
+            attempt += 1;
+        }
+    }
+
+    /// The digit the value ends with at this place, if it's within its error
+    /// of a value that ends there, the way `certify` takes digits that end.
+    fn ending_digit(&self) -> Option<i64> {
+        let digit = ((&self.remainder + &self.error) >> self.bits)
+            .to_i64()?
+            .clamp(0, self.largest_digit);
+        let left = &self.remainder - (BigInt::from(digit) << self.bits);
+        (left.abs() <= self.error).then_some(digit)
+    }
+
+    /// Leave nothing below the digits given so far, so only the zeros down to
+    /// the point are left.
+    fn end(&mut self) {
+        self.remainder = BigInt::zero();
+        self.error = BigInt::zero();
+    }
+
+    /// Take a digit out of the remainder, and move on to the next place.
+    fn give(&mut self, digit: i64) -> (i64, isize) {
+        let fraction = &self.remainder - (BigInt::from(digit) << self.bits);
+        let (base, base_error) = &self.fixed_base;
+        if !(fraction.is_zero() && self.error.is_zero()) {
+            self.error = ((&self.error * (base + base_error) + fraction.abs() * base_error)
+                >> self.bits)
+                + 2;
+        }
+        self.remainder = (fraction * base) >> self.bits;
+        self.given.push(digit);
+        self.exp -= 1;
+        (digit, self.exp + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bases::MAX_PRECISION;
+
+    fn digits(input: &str, input_base: &str, output_base: &str) -> OutputDigits {
+        BaseConversion::for_test(input, input_base, output_base)
+            .output_digits()
+            .unwrap()
+    }
+
+    fn first_digits(
+        input: &str,
+        input_base: &str,
+        output_base: &str,
+        n: usize,
+    ) -> Vec<(i64, isize)> {
+        digits(input, input_base, output_base)
+            .take(n)
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn lists_exact_digits_lazily() {
+        let sevenths = first_digits("1/7", "10", "10", 8);
+        assert_eq!(
+            vec![
+                (0, 0),
+                (1, -1),
+                (4, -2),
+                (2, -3),
+                (8, -4),
+                (5, -5),
+                (7, -6),
+                (1, -7)
+            ],
+            sevenths
+        );
+        let half = digits("-0.5", "10", "2");
+        assert!(half.is_negative());
+        assert_eq!(vec![Ok((0, 0)), Ok((1, -1))], half.collect::<Vec<_>>());
+        let hundred = first_digits("100", "10", "10", usize::MAX);
+        assert_eq!(vec![(1, 2), (0, 1), (0, 0)], hundred);
+    }
+
+    #[test]
+    fn lists_digits_past_the_maximum_precision() {
+        let pi = BaseConversion::for_test("10", "pi", "10");
+        let shown: String = BaseConversion {
+            precision: MAX_PRECISION,
+            ..pi.clone()
+        }
+        .output()
+        .map(|o| o.string)
+        .unwrap()
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect();
+        let listed: String = pi
+            .output_digits()
+            .unwrap()
+            .take(MAX_PRECISION + 50)
+            .map(|d| char::from_digit(d.unwrap().0 as u32, 10).unwrap())
+            .collect();
+        assert_eq!(shown, listed[..shown.len()]);
+        assert_eq!(MAX_PRECISION + 50, listed.len());
+    }
+
+    #[test]
+    fn finds_runs_of_digits() {
+        // The Feynman point: six 9s, starting 762 places after the point
+        let mut run = 0;
+        let start = digits("10", "pi", "10").find_map(|d| {
+            let (d, exp) = d.unwrap();
+            run = if d == 9 { run + 1 } else { 0 };
+            (run == 6).then_some(exp + 5)
+        });
+        assert_eq!(Some(-762), start);
+    }
+
+    #[test]
+    fn gives_the_sign_apart_from_the_digits() {
+        assert_eq!(5, first_digits("0.1", "3", "e", 5).len());
+        assert!(!digits("0.1", "3", "e").is_negative());
+        assert!(digits("-10", "10", "pi").is_negative());
+    }
+
+    #[test]
+    fn ends_where_the_value_does() {
+        // Values on a digit boundary in an irrational base can only be known
+        // to be close to it, so they end wherever they're close to ending
+        for (input, input_base, output_base) in [
+            ("10", "phi", "phi"),
+            ("2", "10", "phi"),
+            ("1", "10", "sqrt2"),
+            ("2", "10", "sqrt2"),
+        ] {
+            let conversion = BaseConversion::for_test(input, input_base, output_base);
+            let written: String = conversion
+                .output_digits()
+                .unwrap()
+                .map(|d| {
+                    let (digit, exp) = d.unwrap();
+                    let point = if exp == -1 { "." } else { "" };
+                    format!("{point}{digit}")
+                })
+                .collect();
+            assert_eq!(conversion.output().unwrap().string, written);
+        }
+        assert!(BaseConversion::for_test("1", "10", "-10")
+            .output_digits()
+            .is_err());
+    }
+
+    #[test]
+    fn gives_an_error_for_digits_too_large_to_list() {
+        // The only digit of a value less than the base is the value itself
+        let huge: Vec<_> = digits(&"1".repeat(24), "10", &format!("1{}", "0".repeat(24))).collect();
+        assert!(matches!(huge[..], [Err(ConversionError::Unsupported(_))]));
+    }
+}
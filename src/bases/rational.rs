@@ -0,0 +1,435 @@
+use std::{collections::HashMap, str::FromStr};
+
+use bigdecimal::{num_bigint::BigInt, BigDecimal, One, Signed, ToPrimitive, Zero};
+use num_rational::BigRational;
+
+use super::{
+    div,
+    integer::{integer_from_digits, integer_to_digits},
+    parse_digit, rep_to_digit_exponent_pairs, split_complex, split_exponent, split_sign, Complex,
+    Digits, Expression, Rounding, MIN_WORKING_PLACES,
+};
+
+/// Repeating expansions that need more fractional digits than this, or than
+/// the output precision if that's more, are cut off like irrational ones
+/// instead.
+static MAX_REPEATING_DIGITS: isize = 100;
+
+pub(super) fn rational_from_decimal(value: &BigDecimal) -> BigRational {
+    let (digits, scale) = value.as_bigint_and_exponent();
+    match scale >= 0 {
+        true => BigRational::new(digits, BigInt::from(10).pow(scale as u32)),
+        false => BigRational::from_integer(digits * BigInt::from(10).pow(-scale as u32)),
+    }
+}
+
+/// The decimal value of a rational, rounded to the given number of decimal
+/// places.
+pub(super) fn decimal_from_rational(value: &BigRational, places: i64) -> BigDecimal {
+    div(
+        &BigDecimal::from(value.numer().clone()),
+        &BigDecimal::from(value.denom().clone()),
+        places,
+    )
+}
+
+/// The exact value of a base, if it's written as a decimal number or as an
+/// expression with a rational value, like `10/3` or `hex`.
+pub(super) fn exact_base(s: &str) -> Option<BigRational> {
+    BigDecimal::from_str(s.trim())
+        .ok()
+        .map(|b| rational_from_decimal(&b))
+        .or_else(|| Expression::from_str(s).ok().and_then(|e| e.exact()))
+        .filter(|b| b.abs() > BigRational::one())
+}
+
+/// The value of a string of digits, ignoring any radix point.
+///
+/// Integer bases convert the digits as an integer, in blocks, so that long
+/// inputs don't take a multiplication by the base for every digit.
+fn digits_val_from_base(digits: &str, base: &BigRational) -> Option<BigRational> {
+    let complex_base = Complex::from(decimal_from_rational(base, MIN_WORKING_PLACES));
+    let digits = rep_to_digit_exponent_pairs(digits)
+        .into_iter()
+        .filter(|(char, _)| char != ".")
+        .map(|(char, _)| parse_digit(&char, &complex_base).ok())
+        .collect::<Option<Vec<_>>>()?;
+    match base.is_integer() {
+        true => Some(BigRational::from_integer(integer_from_digits(
+            &digits,
+            base.numer(),
+        ))),
+        false => Some(
+            digits
+                .into_iter()
+                .fold(BigRational::zero(), |sum, d| sum * base + BigInt::from(d)),
+        ),
+    }
+}
+
+/// The exact value of a real representation in a rational base.
+///
+/// The representation may be a fraction like `1/7`, and may end with repeating
+/// digits in parentheses, like `0.1(6)`, or with an exponent, like `1.2e-30`.
+pub(super) fn exact_val_from_base(input: &str, base: &BigRational) -> Option<BigRational> {
+    if let Some((numerator, denominator)) = input.split_once('/') {
+        let denominator = exact_val_from_base(denominator, base).filter(|d| !d.is_zero())?;
+        return Some(exact_val_from_base(numerator, base)? / denominator);
+    }
+    let e_is_digit = base.abs() > BigRational::from_integer(14.into());
+    let (input, exp) = split_exponent(input, e_is_digit).ok()?;
+    if split_complex(input).is_some() && base.abs() <= BigRational::from_integer(18.into()) {
+        return None;
+    }
+    let (is_negative, input) = split_sign(input);
+    let (head, block) = match input.split_once('(') {
+        Some((head, block)) => (head, block.strip_suffix(')').filter(|b| !b.is_empty())?),
+        None => (input, ""),
+    };
+    let (_, fraction) = head.split_once('.').unwrap_or((head, ""));
+    if fraction.contains('.') || block.contains(['(', ')', '.']) {
+        return None;
+    }
+    // Repeating digits must come after the point, as they must inexactly
+    if !block.is_empty() && !head.contains('.') {
+        return None;
+    }
+
+    let shift = base.pow(rep_to_digit_exponent_pairs(fraction).len() as i32);
+    let mut magnitude = digits_val_from_base(head, base)?;
+    // Dividing reduces the fraction, which takes a while for long integers
+    if !shift.is_one() {
+        magnitude /= shift.clone();
+    }
+    if !block.is_empty() {
+        let period = base.pow(rep_to_digit_exponent_pairs(block).len() as i32);
+        magnitude += digits_val_from_base(block, base)? / (period - BigRational::one()) / shift;
+    }
+    if let Some(exp) = exp {
+        magnitude *= base.pow(exp as i32);
+    }
+    match is_negative {
+        true => Some(-magnitude),
+        false => Some(magnitude),
+    }
+}
+
+/// Write a rational value in a rational base without losing precision.
+///
+/// Positive integer bases write every digit of the value, with any repeating
+/// digits marked, unless that takes too many digits. Otherwise the digits are
+/// cut off after `-precision - 1` fractional digits.
+pub(super) fn rational_to_digits(
+    value: &BigRational,
+    base: &BigRational,
+    precision: isize,
+) -> Option<Digits> {
+    if base.is_integer() && base > &BigRational::one() {
+        Some(rational_to_integer_base(value, base.numer(), precision))
+    } else if base > &BigRational::one() {
+        Some(greedy_rational_to_base(value, base, precision))
+    } else if base < &-BigRational::one() {
+        Some(rational_to_negative_base(value, base, precision))
+    } else {
+        None
+    }
+}
+
+/// Long division only has as many possible remainders as the denominator, so
+/// the digits repeat as soon as a remainder does.
+fn rational_to_integer_base(value: &BigRational, k: &BigInt, precision: isize) -> Digits {
+    let denominator = value.denom().clone();
+    let numerator = value.numer().abs();
+    let (integer, mut remainder) = (numerator.clone() / &denominator, numerator % &denominator);
+
+    let integer_digits = integer_to_digits(&integer, k);
+    let mut pairs: Vec<_> = integer_digits.into_iter().rev().zip(0_isize..).collect();
+    pairs.reverse();
+
+    let mut seen = HashMap::new();
+    let mut repeating_from = None;
+    let mut is_elided = false;
+    let mut exp = -1;
+    let max_digits = MAX_REPEATING_DIGITS.max(-precision);
+    while !remainder.is_zero() {
+        if let Some(&start) = seen.get(&remainder) {
+            repeating_from = Some(start);
+            break;
+        }
+        if exp < -max_digits {
+            pairs.retain(|&(_, e)| e > precision);
+            is_elided = true;
+            break;
+        }
+        seen.insert(remainder.clone(), exp);
+        remainder *= k;
+        pairs.push(((remainder.clone() / &denominator).to_i64().unwrap(), exp));
+        remainder %= &denominator;
+        exp -= 1;
+    }
+
+    Digits {
+        is_negative: value.is_negative(),
+        repeating_from,
+        ..Digits::new(pairs, is_elided)
+    }
+}
+
+/// Greedily take the largest multiple of each power of the base that fits.
+fn greedy_rational_to_base(value: &BigRational, base: &BigRational, precision: isize) -> Digits {
+    let mut remaining = value.abs();
+    let mut exp = 0;
+    let mut power = BigRational::one();
+    while power.clone() * base <= remaining {
+        exp += 1;
+        power *= base;
+    }
+
+    let mut pairs = Vec::new();
+    let mut is_elided = false;
+    while !remaining.is_zero() || exp >= 0 {
+        if exp == precision {
+            is_elided = true;
+            break;
+        }
+        let digit = (remaining.clone() / power.clone()).floor();
+        remaining -= digit.clone() * power.clone();
+        pairs.push((digit.to_integer().to_i64().unwrap(), exp));
+        exp -= 1;
+        power /= base;
+    }
+
+    Digits {
+        is_negative: value.is_negative(),
+        ..Digits::new(pairs, is_elided)
+    }
+}
+
+/// The exact counterpart of `val_to_negative_base`, choosing digits with the
+/// Ito-Sadahiro rule.
+fn rational_to_negative_base(value: &BigRational, base: &BigRational, precision: isize) -> Digits {
+    let magnitude = base.abs();
+    let lower = -(magnitude.clone() / (magnitude.clone() + BigRational::one()));
+    let upper = lower.clone() + BigRational::one();
+    let max_digit = magnitude.ceil() - BigRational::one();
+
+    let mut remaining = value.clone();
+    let mut exp = 0;
+    let mut power = BigRational::one();
+    while {
+        let scaled = remaining.clone() / power.clone() / base;
+        scaled < lower || scaled >= upper
+    } {
+        exp += 1;
+        power *= base;
+    }
+
+    let mut pairs = Vec::new();
+    while !remaining.is_zero() || exp >= 0 {
+        if exp == precision {
+            return Digits::new(pairs, true);
+        }
+        let digit = (remaining.clone() / power.clone() - lower.clone())
+            .floor()
+            .max(BigRational::zero())
+            .min(max_digit.clone());
+        remaining -= digit.clone() * power.clone();
+        pairs.push((digit.to_integer().to_i64().unwrap(), exp));
+        exp -= 1;
+        power /= base;
+    }
+
+    Digits::new(pairs, false)
+}
+
+/// Write a rational value in a rational base greater than 1, cut off after
+/// `-precision - 1` fractional digits and rounded the same way `certify`
+/// rounds approximate values, along with how far the digits are from the
+/// value.
+///
+/// Adding half of the last place value first rounds to the nearest last
+/// digit, carrying into the digits above it wherever needed. Nothing is left
+/// over below the last digit only on a tie, which rounding half to even takes
+/// back when the last digit is odd.
+pub(super) fn rational_to_rounded_digits(
+    value: &BigRational,
+    base: &BigRational,
+    precision: isize,
+    rounding: Rounding,
+) -> Option<(Digits, BigRational)> {
+    if base <= &BigRational::one() {
+        return None;
+    }
+    let last = precision + 1;
+    let place_value = base.pow(last as i32);
+    let half = match rounding {
+        Rounding::Truncate => BigRational::zero(),
+        Rounding::HalfEven | Rounding::HalfUp => &place_value / BigInt::from(2),
+    };
+    let shifted = value.abs() + &half;
+    let (mut pairs, mut remaining) = match base.is_integer() {
+        // Integer bases write every digit down to the last at once
+        true => {
+            let scaled = &shifted / &place_value;
+            let integer = scaled.to_integer();
+            let remaining = (scaled - BigRational::from_integer(integer.clone())) * &place_value;
+            let mut digits = integer_to_digits(&integer, base.numer());
+            let leading = (1 - last - digits.len() as isize).max(0) as usize;
+            digits.splice(..0, std::iter::repeat_n(0, leading));
+            let top = last + digits.len() as isize - 1;
+            (
+                digits.into_iter().zip((last..=top).rev()).collect(),
+                remaining,
+            )
+        }
+        false => {
+            let mut remaining = shifted;
+            let mut exp = 0;
+            let mut power = BigRational::one();
+            while power.clone() * base <= remaining {
+                exp += 1;
+                power *= base;
+            }
+            let mut pairs = Vec::new();
+            while exp >= last {
+                let digit = (remaining.clone() / power.clone()).floor();
+                remaining -= digit.clone() * power.clone();
+                pairs.push((digit.to_integer().to_i64().unwrap(), exp));
+                exp -= 1;
+                power /= base;
+            }
+            (pairs, remaining)
+        }
+    };
+
+    if rounding == Rounding::HalfEven && remaining.is_zero() {
+        if let Some((digit, _)) = pairs
+            .iter_mut()
+            .find(|&&mut (d, e)| e == last && d % 2 != 0)
+        {
+            *digit -= 1;
+            remaining = place_value;
+        }
+    }
+    let digits = match rounding {
+        Rounding::Truncate => Digits::new(pairs, !remaining.is_zero()),
+        Rounding::HalfEven | Rounding::HalfUp => Digits::from_positions(pairs, false),
+    };
+    let is_zero = digits.pairs.iter().all(|&(d, _)| d == 0);
+    Some((
+        Digits {
+            is_negative: value.is_negative() && !is_zero,
+            ..digits
+        },
+        (remaining - half).abs(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ratio(numerator: i64, denominator: i64) -> BigRational {
+        BigRational::new(BigInt::from(numerator), BigInt::from(denominator))
+    }
+
+    fn show(value: BigRational, base: BigRational) -> String {
+        rational_to_digits(&value, &base, -9).unwrap().to_string()
+    }
+
+    #[test]
+    fn shows_repeating_fractions() {
+        let ten = ratio(10, 1);
+        assert_eq!("0.(142857)", show(ratio(1, 7), ten.clone()));
+        assert_eq!("0.1(6)", show(ratio(1, 6), ten.clone()));
+        assert_eq!("0.(0022)", show(ratio(1, 10), ratio(3, 1)));
+        assert_eq!("-12.(10)", show(ratio(-1198, 99), ten));
+    }
+
+    #[test]
+    fn finds_longer_periods_at_higher_precision() {
+        let ten = ratio(10, 1);
+        assert_eq!("0.00917431…", show(ratio(1, 109), ten.clone()));
+        let digits = rational_to_digits(&ratio(1, 109), &ten, -121).unwrap();
+        assert_eq!(Some(-1), digits.repeating_from);
+        assert_eq!(108, digits.pairs.len() - 1);
+    }
+
+    #[test]
+    fn shows_terminating_fractions_in_full() {
+        assert_eq!("0.0001", show(ratio(1, 16), ratio(2, 1)));
+        assert_eq!("255", show(ratio(255, 1), ratio(10, 1)));
+        assert_eq!("0", show(ratio(0, 1), ratio(10, 1)));
+    }
+
+    #[test]
+    fn cuts_off_long_periods() {
+        let value = ratio(1, 5_i64.pow(20));
+        assert_eq!("0.00000000…", show(value, ratio(2, 1)));
+    }
+
+    #[test]
+    fn shows_values_in_rational_bases() {
+        assert_eq!("1000", show(ratio(27, 8), ratio(3, 2)));
+        assert_eq!("-100", show(ratio(-9, 4), ratio(3, 2)));
+        assert_eq!("11010", show(ratio(6, 1), ratio(-2, 1)));
+        assert_eq!("11", show(ratio(-1, 1), ratio(-2, 1)));
+    }
+
+    #[test]
+    fn reads_exact_values() {
+        assert_eq!(
+            Some(ratio(-1, 7)),
+            exact_val_from_base("-0.1", &ratio(7, 1))
+        );
+        assert_eq!(Some(ratio(5, 3)), exact_val_from_base("1.1", &ratio(3, 2)));
+        assert_eq!(None, exact_val_from_base("1+i", &ratio(10, 1)));
+    }
+
+    #[test]
+    fn reads_exact_repeating_values_and_fractions() {
+        let ten = ratio(10, 1);
+        assert_eq!(Some(ratio(1, 3)), exact_val_from_base("0.(3)", &ten));
+        assert_eq!(Some(ratio(1, 6)), exact_val_from_base("0.1(6)", &ten));
+        assert_eq!(Some(ratio(-1, 7)), exact_val_from_base("-1/7", &ten));
+        assert_eq!(
+            Some(ratio(2, 3)),
+            exact_val_from_base("10/11", &ratio(2, 1))
+        );
+        assert_eq!(
+            Some(ratio(1, 1)),
+            exact_val_from_base("0.(1)", &ratio(2, 1))
+        );
+        assert_eq!(None, exact_val_from_base("1/0", &ten));
+        assert_eq!(None, exact_val_from_base("0.()", &ten));
+        assert_eq!(None, exact_val_from_base("1(2)", &ten));
+    }
+
+    #[test]
+    fn reads_exact_exponents() {
+        let ten = ratio(10, 1);
+        assert_eq!(Some(ratio(3, 2500)), exact_val_from_base("1.2e-3", &ten));
+        assert_eq!(Some(ratio(1, 30)), exact_val_from_base("1/3E1", &ten));
+        assert_eq!(Some(ratio(-3, 2)), exact_val_from_base("-1.5e0", &ten));
+        assert_eq!(
+            Some(ratio(36, 1)),
+            exact_val_from_base("11×10^2", &ratio(3, 1))
+        );
+        assert_eq!(
+            Some(ratio(482, 1)),
+            exact_val_from_base("1e2", &ratio(16, 1))
+        );
+        assert_eq!(
+            Some(ratio(7936, 1)),
+            exact_val_from_base("1F*10^2", &ratio(16, 1))
+        );
+    }
+
+    #[test]
+    fn only_decimal_and_integer_bases_are_exact() {
+        assert_eq!(Some(ratio(3, 2)), exact_base("1.5"));
+        assert_eq!(Some(ratio(16, 1)), exact_base("hex"));
+        assert_eq!(None, exact_base("pi"));
+        assert_eq!(None, exact_base("1"));
+    }
+}
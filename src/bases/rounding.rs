@@ -0,0 +1,465 @@
+use std::{fmt, str::FromStr};
+
+use bigdecimal::{num_bigint::BigInt, BigDecimal, One, RoundingMode, Signed, Zero};
+
+use super::{
+    div, fixed_point_bits, to_fixed, val_to_digits_to_places, working_places, ConversionError,
+    Digits, GUARD_PLACES,
+};
+
+/// How the last digit of the output is chosen when the value has more digits
+/// than are shown.
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum Rounding {
+    /// Cut the value off after the last digit, marking the cut with `…`
+    #[default]
+    Truncate,
+    /// Round to the nearest last digit, or to an even last digit on a tie
+    HalfEven,
+    /// Round to the nearest last digit, or away from zero on a tie
+    HalfUp,
+}
+
+impl FromStr for Rounding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "truncate" | "down" => Ok(Self::Truncate),
+            "half-even" | "even" => Ok(Self::HalfEven),
+            "half-up" | "up" => Ok(Self::HalfUp),
+            _ => Err(format!("Unrecognized rounding: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Rounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncate => write!(f, "truncate"),
+            Self::HalfEven => write!(f, "half-even"),
+            Self::HalfUp => write!(f, "half-up"),
+        }
+    }
+}
+
+/// A unit in the last of the given decimal places, which bounds the error of
+/// rounding to them.
+fn ulp(places: i64) -> BigDecimal {
+    BigDecimal::new(1.into(), places)
+}
+
+/// Round an error bound up to a few more places than the values it's the
+/// error of, so that tracking it doesn't take more and more digits.
+fn round_up(error: BigDecimal, places: i64) -> BigDecimal {
+    error.with_scale_round(places + GUARD_PLACES, RoundingMode::Up)
+}
+
+/// A product `(a ± α)(b ± β)` rounded to the given decimal places, along with
+/// a bound on its error: `|a|β + |b|α + αβ`, plus a unit in the last place for
+/// the rounding.
+pub(super) fn mul_with_error(
+    (a, a_error): (&BigDecimal, &BigDecimal),
+    (b, b_error): (&BigDecimal, &BigDecimal),
+    places: i64,
+) -> (BigDecimal, BigDecimal) {
+    let error = a.abs() * b_error + b.abs() * a_error + a_error * b_error + ulp(places);
+    ((a * b).round(places).normalized(), round_up(error, places))
+}
+
+/// `pow`, along with a bound on its error given a bound on the error of the
+/// base.
+///
+/// This follows the same steps as `pow`, and tracks how far each one can be
+/// off: each product as in `mul_with_error`, and an inverse `1 / (a ± α)` by
+/// at most `α / (|a| (|a| - α))`, plus a unit in the last place for rounding.
+pub(super) fn pow_with_error(
+    base: &BigDecimal,
+    base_error: &BigDecimal,
+    exp: isize,
+    places: i64,
+) -> (BigDecimal, BigDecimal) {
+    match exp {
+        0 => (BigDecimal::one(), BigDecimal::zero()),
+        1 => (
+            base.clone().round(places).normalized(),
+            base_error + ulp(places),
+        ),
+        n if n < 0 => {
+            let (power, error) = pow_with_error(base, base_error, -n, places);
+            let magnitude = power.abs();
+            let inverse_error = div(&error, &(&magnitude * (&magnitude - &error)), places);
+            (
+                div(&BigDecimal::one(), &power, places),
+                round_up(inverse_error + ulp(places), places),
+            )
+        }
+        n if n % 2 == 0 => {
+            let (square, square_error) =
+                mul_with_error((base, base_error), (base, base_error), places);
+            pow_with_error(&square, &square_error, n / 2, places)
+        }
+        n => {
+            let (power, error) = pow_with_error(base, base_error, n - 1, places);
+            mul_with_error((base, base_error), (&power, &error), places)
+        }
+    }
+}
+
+/// A value and a bound on its error in fixed point with the given number of
+/// binary places, rounded so that the error still bounds it.
+pub(super) fn fixed_with_error(
+    (value, error): (BigDecimal, BigDecimal),
+    bits: usize,
+) -> (BigInt, BigInt) {
+    (to_fixed(&value, bits), to_fixed(&error, bits) + 2)
+}
+
+/// A number in fixed point with the given number of binary places, written
+/// exactly in decimal.
+fn from_fixed(num: BigInt, bits: usize) -> BigDecimal {
+    BigDecimal::new(num * BigInt::from(5).pow(bits as u32), bits as i64)
+}
+
+/// The place values of the exponents from `top` down to `bottom` in a real
+/// base, in fixed point with the given number of binary places, along with
+/// bounds on their errors. Each one is worked out from the one above it in
+/// integer arithmetic, so long outputs don't need a power of the base for
+/// every digit.
+fn place_values(
+    top: isize,
+    bottom: isize,
+    base: &BigDecimal,
+    base_error: &BigDecimal,
+    places: i64,
+) -> Vec<(BigInt, BigInt)> {
+    let bits = fixed_point_bits(places);
+    let (inverse, inverse_error) =
+        fixed_with_error(pow_with_error(base, base_error, -1, places), bits);
+    let top_value = pow_with_error(base, base_error, top, places);
+    let mut place_values = vec![fixed_with_error(top_value, bits)];
+    for _ in bottom..top {
+        let (power, error) = place_values.last().unwrap();
+        let next_error =
+            (power * &inverse_error + &inverse * error + error * &inverse_error) >> bits;
+        place_values.push(((power * &inverse) >> bits, next_error + 2));
+    }
+    place_values
+}
+
+/// The value of digits in a real base, along with a bound on the error of
+/// working it out, in fixed point with as many binary places as
+/// `fixed_point_bits` gives for the decimal places.
+fn digits_value(
+    digits: &Digits,
+    base: &BigDecimal,
+    base_error: &BigDecimal,
+    places: i64,
+) -> (BigInt, BigInt) {
+    let (Some(&(_, top)), Some(&(_, bottom))) = (digits.pairs.first(), digits.pairs.last()) else {
+        return (BigInt::zero(), BigInt::zero());
+    };
+    let place_values = place_values(top, bottom, base, base_error, places);
+    let (magnitude, error) = digits.pairs.iter().fold(
+        (BigInt::zero(), BigInt::zero()),
+        |(sum, error), &(digit, exp)| {
+            let (power, power_error) = &place_values[(top - exp) as usize];
+            (sum + power * digit, error + power_error * digit.abs())
+        },
+    );
+    match digits.is_negative {
+        true => (-magnitude, error),
+        false => (magnitude, error),
+    }
+}
+
+/// How sure the digits worked out for a value are.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub(super) enum Certainty {
+    /// Every value within the error of the value has the same digits
+    Certain,
+    /// The value is within its error of where the digits end, like the `10`
+    /// that writes the base itself, or a tie when rounding. The digits are
+    /// right as long as the value really does end there.
+    Ends,
+    /// The digits at and below this exponent could be different
+    UncertainFrom(isize),
+}
+
+/// Output digits, along with how sure they are and a bound on how far their
+/// value can be from the value they were written for.
+pub(super) struct Certified {
+    pub(super) digits: Digits,
+    pub(super) certainty: Certainty,
+    pub(super) error: BigDecimal,
+}
+
+/// Check that positive digits are the greedy digits of every value within the
+/// error of a value, down to the `last` exponent.
+///
+/// Greedy digits leave a remainder below each digit that's no less than 0 and
+/// less than the place value of the digit, so each remainder has to be that
+/// far from both ends to be sure of the digit. Returns whether anything is
+/// certainly left below the digits, or else the exponent of the first digit
+/// that could be different.
+fn check_greedy(
+    digits: &Digits,
+    value: &BigDecimal,
+    value_error: &BigDecimal,
+    base: &BigDecimal,
+    base_error: &BigDecimal,
+    last: isize,
+    places: i64,
+) -> Result<bool, isize> {
+    let bits = fixed_point_bits(places);
+    let top = digits.pairs.first().map_or(last, |&(_, e)| e);
+    let bottom = digits.pairs.last().map_or(top, |&(_, e)| e);
+    let place_values = place_values(top + 1, bottom, base, base_error, places);
+    let last_place_value = fixed_with_error(pow_with_error(base, base_error, last, places), bits);
+    let fits = |remainder: &BigInt, error: &BigInt, exp: isize| {
+        let (power, power_error) = match exp < bottom {
+            true => &last_place_value,
+            false => &place_values[(top + 1 - exp) as usize],
+        };
+        remainder >= error && remainder + error < power - power_error
+    };
+
+    let (mut remainder, mut error) = fixed_with_error((value.clone(), value_error.clone()), bits);
+    if !fits(&remainder, &error, top + 1) {
+        return Err(top);
+    }
+    for &(digit, exp) in &digits.pairs {
+        let (power, power_error) = &place_values[(top + 1 - exp) as usize];
+        error += power_error * digit.abs();
+        remainder -= power * digit;
+        if !fits(&remainder, &error, exp) {
+            return Err(exp);
+        }
+    }
+    match fits(&remainder, &error, last) {
+        true => Ok(remainder > error),
+        false => Err(last),
+    }
+}
+
+/// Write a value in a base greater than 1 with the standard digits, and check
+/// the digits against the errors of the value and of the base.
+///
+/// The digits are cut off after the last of the `-precision - 1` fractional
+/// digits. Adding half of the last place value first rounds to the nearest
+/// last digit, carrying into the digits above it wherever needed.
+///
+/// However each digit was worked out, `check_greedy` makes sure of it. When
+/// it can't, the value could be where some digits end, which is where greedy
+/// digits end if they can. So the digits of the value nudged up by its error
+/// are used instead if they end within the error of the value, and rounding
+/// half to even then takes back the carry into an odd last digit of a tie.
+/// Otherwise, the digits from the first one that could be different are
+/// marked uncertain.
+pub(super) fn certify(
+    value: &BigDecimal,
+    value_error: &BigDecimal,
+    base: &BigDecimal,
+    base_error: &BigDecimal,
+    precision: isize,
+    rounding: Rounding,
+    places: i64,
+) -> Result<Certified, ConversionError> {
+    let last = precision + 1;
+    let bits = fixed_point_bits(places);
+    let (half, half_error) = match rounding {
+        Rounding::Truncate => (BigDecimal::zero(), BigDecimal::zero()),
+        Rounding::HalfEven | Rounding::HalfUp => {
+            let (place_value, place_error) = pow_with_error(base, base_error, last, places);
+            (place_value.half(), place_error.half())
+        }
+    };
+    let shifted = value.abs() + half;
+    let shifted_error = value_error + half_error;
+    let digits = val_to_digits_to_places(&shifted, base, precision, places)?;
+
+    let checked = check_greedy(
+        &digits,
+        &shifted,
+        &shifted_error,
+        base,
+        base_error,
+        last,
+        places,
+    );
+    let (mut digits, certainty) = match checked {
+        Ok(is_cut_off) => {
+            let is_elided = digits.is_elided || is_cut_off;
+            (
+                Digits {
+                    is_elided,
+                    ..digits
+                },
+                Certainty::Certain,
+            )
+        }
+        Err(from) => {
+            let (_, shown_error) = digits_value(&digits, base, base_error, places);
+            let nudged = &shifted + &shifted_error + from_fixed(shown_error, bits);
+            let up = val_to_digits_to_places(&nudged, base, precision, places)?;
+            let (up_shown, up_error) = digits_value(&up, base, base_error, places);
+            let (shifted, shifted_error) = fixed_with_error((shifted, shifted_error), bits);
+            match !up.is_elided && (shifted - up_shown).abs() <= shifted_error + up_error {
+                true => (up, Certainty::Ends),
+                false => (digits, Certainty::UncertainFrom(from)),
+            }
+        }
+    };
+
+    if certainty == Certainty::Ends && rounding == Rounding::HalfEven {
+        if let Some((digit, _)) = digits
+            .pairs
+            .iter_mut()
+            .find(|&&mut (d, e)| e == last && d % 2 != 0)
+        {
+            *digit -= 1;
+        }
+    }
+    if rounding != Rounding::Truncate {
+        digits = Digits::from_positions(digits.pairs, false);
+    }
+    let is_zero = digits.pairs.iter().all(|&(d, _)| d == 0);
+    let digits = Digits {
+        is_negative: value.is_negative() && !is_zero,
+        uncertain_from: match certainty {
+            Certainty::UncertainFrom(from) => Some(from),
+            Certainty::Certain | Certainty::Ends => None,
+        },
+        ..digits
+    };
+    let (shown, shown_error) = digits_value(&digits, base, base_error, places);
+    let (value, value_error) = fixed_with_error((value.clone(), value_error.clone()), bits);
+    let error = (value - shown).abs() + value_error + shown_error;
+    Ok(Certified {
+        error: round_up(from_fixed(error, bits), places),
+        digits,
+        certainty,
+    })
+}
+
+/// Write a value in a base greater than 1 with the standard digits, rounding
+/// the last of the `-precision - 1` fractional digits. The value is taken to
+/// be off by a little less than the guard places it was worked out with.
+pub(super) fn val_to_rounded_digits(
+    value: &BigDecimal,
+    base: &BigDecimal,
+    precision: isize,
+    rounding: Rounding,
+) -> Result<Digits, ConversionError> {
+    let places = working_places(precision, base);
+    let value_error = ulp(places - GUARD_PLACES);
+    certify(
+        value,
+        &value_error,
+        base,
+        &BigDecimal::zero(),
+        precision,
+        rounding,
+        places,
+    )
+    .map(|certified| certified.digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bases::{constant, pow};
+
+    fn rounded(value: &str, base: u32, precision: isize, rounding: Rounding) -> String {
+        let value = BigDecimal::from_str(value).unwrap();
+        val_to_rounded_digits(&value, &BigDecimal::from(base), precision, rounding)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn rounds_the_last_digit() {
+        assert_eq!("0.33", rounded("0.333", 10, -3, Rounding::HalfUp));
+        assert_eq!("0.67", rounded("0.666", 10, -3, Rounding::HalfUp));
+        assert_eq!("0.66…", rounded("0.666", 10, -3, Rounding::Truncate));
+        assert_eq!("-0.67", rounded("-0.666", 10, -3, Rounding::HalfEven));
+    }
+
+    #[test]
+    fn carries_across_digits() {
+        assert_eq!("100", rounded("99.96", 10, -2, Rounding::HalfUp));
+        assert_eq!("10", rounded("1.96875", 2, -3, Rounding::HalfUp));
+    }
+
+    #[test]
+    fn breaks_ties() {
+        assert_eq!("0.3", rounded("0.25", 10, -2, Rounding::HalfUp));
+        assert_eq!("0.2", rounded("0.25", 10, -2, Rounding::HalfEven));
+        assert_eq!("0.4", rounded("0.35", 10, -2, Rounding::HalfEven));
+        assert_eq!("0", rounded("-0.4", 10, -1, Rounding::HalfEven));
+    }
+
+    #[test]
+    fn bounds_the_error() {
+        let pi = constant("pi", 80).unwrap();
+        let base = BigDecimal::from(10);
+        let certified = certify(
+            &pi,
+            &ulp(60),
+            &base,
+            &BigDecimal::zero(),
+            -5,
+            Rounding::HalfUp,
+            60,
+        )
+        .unwrap();
+        assert_eq!("3.1416", certified.digits.to_string());
+        assert_eq!(Certainty::Certain, certified.certainty);
+        assert!(certified.error > BigDecimal::from_str("0.0000073").unwrap());
+        assert!(certified.error < BigDecimal::from_str("0.0000074").unwrap());
+
+        let (power, error) = pow_with_error(&pi, &ulp(60), -20, 60);
+        assert!((power - pow(&pi, -20, 80)).abs() <= error);
+    }
+
+    #[test]
+    fn settles_on_where_digits_end() {
+        let phi = constant("phi", 80).unwrap();
+        let certified = certify(&phi, &ulp(50), &phi, &ulp(50), -9, Rounding::Truncate, 60);
+        let certified = certified.unwrap();
+        assert_eq!("10", certified.digits.to_string());
+        assert_eq!(Certainty::Ends, certified.certainty);
+
+        let nearly = BigDecimal::from_str("0.2").unwrap() - ulp(55);
+        let ten = BigDecimal::from(10);
+        let certified = certify(
+            &nearly,
+            &ulp(50),
+            &ten,
+            &ulp(60),
+            -3,
+            Rounding::Truncate,
+            60,
+        );
+        assert_eq!("0.2", certified.unwrap().digits.to_string());
+    }
+
+    #[test]
+    fn flags_uncertain_digits() {
+        let value = BigDecimal::from_str("0.155").unwrap();
+        let error = BigDecimal::from_str("0.01").unwrap();
+        let ten = BigDecimal::from(10);
+        let certified = certify(
+            &value,
+            &error,
+            &ten,
+            &BigDecimal::zero(),
+            -3,
+            Rounding::Truncate,
+            60,
+        );
+        let certified = certified.unwrap();
+        assert_eq!("0.1?…", certified.digits.to_string());
+        assert_eq!(Certainty::UncertainFrom(-2), certified.certainty);
+    }
+}
@@ -0,0 +1,85 @@
+use leptos::{html::*, *};
+
+use crate::bases::BaseConversion;
+
+/// The most output digits to search through, since every digit takes a little
+/// more work than the one before it.
+static MAX_SEARCH_DIGITS: usize = 2000;
+
+/// Where the digits first appear in the output, searching up to
+/// `MAX_SEARCH_DIGITS` digits. The sign of a negative value counts as part of
+/// its leading digit.
+fn search(base_conversion: &BaseConversion, pattern: &str) -> String {
+    let digits = match base_conversion.output_digits() {
+        Ok(digits) => digits,
+        Err(e) => return e.to_string(),
+    };
+    let mut written = String::new();
+    let mut exponents = Vec::new();
+    let mut searched = 0;
+    if digits.is_negative() {
+        written.push('-');
+    }
+    for digit in digits.take(MAX_SEARCH_DIGITS) {
+        let (digit, exp) = match digit {
+            Ok(digit) => digit,
+            Err(e) => return format!("`{pattern}` isn't in the first {searched} digits. {e}."),
+        };
+        searched += 1;
+        // The sign has the exponent of the leading digit
+        exponents.resize(written.chars().count(), exp);
+        let symbol = base_conversion.output_alphabet.symbol(digit);
+        exponents.extend(symbol.chars().map(|_| exp));
+        written.push_str(&symbol);
+        if written.ends_with(pattern) {
+            let start = exponents[exponents.len() - pattern.chars().count()];
+            return match start < 0 {
+                true => format!(
+                    "`{pattern}` first starts at digit {} after the point.",
+                    -start
+                ),
+                false => format!("`{pattern}` first appears at the digit with exponent {start}."),
+            };
+        }
+    }
+    match searched < MAX_SEARCH_DIGITS {
+        true => format!("`{pattern}` doesn't appear in the output."),
+        false => format!("`{pattern}` isn't in the first {MAX_SEARCH_DIGITS} digits."),
+    }
+}
+
+#[component]
+pub fn DigitSearch(base_conversion: Memo<BaseConversion>) -> impl IntoView {
+    let (is_open, set_is_open) = create_signal(false);
+    let (pattern, set_pattern) = create_signal(String::new());
+
+    move || match (base_conversion().output_digits(), is_open()) {
+        (Ok(_), true) => div()
+            .child(
+                button()
+                    .classes("mx-auto")
+                    .on(ev::click, move |_| set_is_open(false))
+                    .child("Hide Digit Search"),
+            )
+            .child(p().child(
+                "The output only shows so many digits, but the digits go on as far as they're \
+                 looked for. Find where some digits first appear:",
+            ))
+            .child(
+                input()
+                    .attr("type", "text")
+                    .attr("value", pattern)
+                    .on(ev::input, move |ev| set_pattern(event_target_value(&ev))),
+            )
+            .child(move || match pattern().is_empty() {
+                true => p(),
+                false => p().child(search(&base_conversion(), &pattern())),
+            }),
+        (Ok(_), false) => div().child(
+            button()
+                .on(ev::click, move |_| set_is_open(true))
+                .child("Search the Digits"),
+        ),
+        (Err(_), _) => div(),
+    }
+}
@@ -2,24 +2,32 @@ use bigdecimal::BigDecimal;
 use leptos::{html::*, *};
 use web_sys::Event;
 
-use crate::bases::BaseConversion;
+use std::str::FromStr;
 
-use super::rounded_bignum::rounded_bignum;
+use crate::bases::{BaseConversion, ConversionError, Output, Rounding, MAX_PRECISION};
+
+use super::rounded_bignum::{rounded_base, rounded_bignum};
 
 #[component]
 pub fn HomeInputs(
     base_conversion: Memo<BaseConversion>,
+    output: Memo<Result<Output, ConversionError>>,
     #[prop(into)] set_input_string: WriteSignal<String>,
     #[prop(into)] set_input_base_string: WriteSignal<String>,
+    #[prop(into)] set_input_digit_set_string: WriteSignal<String>,
     #[prop(into)] set_output_base_string: WriteSignal<String>,
+    #[prop(into)] set_output_digit_set_string: WriteSignal<String>,
+    #[prop(into)] set_precision: WriteSignal<usize>,
+    #[prop(into)] set_exponent_base_string: WriteSignal<String>,
+    #[prop(into)] set_permissive: WriteSignal<bool>,
+    #[prop(into)] set_rounding: WriteSignal<Rounding>,
     #[prop(into)] accurate_conversion: ReadSignal<Option<String>>,
     #[prop(into)] set_accurate_conversion: WriteSignal<Option<String>>,
 ) -> impl IntoView {
     let show_accurate = create_memo(move |_| {
-        base_conversion()
-            .output_string()
+        output()
             .ok()
-            .and_then(|v| accurate_conversion().and_then(|a| if a == v { None } else { Some(a) }))
+            .and_then(|o| accurate_conversion().filter(|a| a != &o.string))
     });
     move || {
         table()
@@ -29,15 +37,21 @@ pub fn HomeInputs(
                     tr().child(th().child("Value in Base-10:"))
                         .child(th().child(div().classes("value").child(move || {
                             match base_conversion().base_10_value() {
-                                Ok(v) => span().child(
-                                    code()
-                                        .child(rounded_bignum(v, None))
-                                        .child(span().inner_html("&nbsp"))
-                                        .child(sub().child(move || {
-                                            rounded_bignum(BigDecimal::from(10), None)
-                                        })),
-                                ),
-                                Err(e) => span().child(e),
+                                Ok(v) => span()
+                                    .child(
+                                        code()
+                                            .child(rounded_bignum(v, None))
+                                            .child(span().inner_html("&nbsp"))
+                                            .child(sub().child(move || {
+                                                rounded_bignum(BigDecimal::from(10), None)
+                                            })),
+                                    )
+                                    .child(
+                                        base_conversion()
+                                            .input_warning()
+                                            .map(|w| div().classes("warning").child(w)),
+                                    ),
+                                Err(e) => input_error(&base_conversion().input_string, e),
                             }
                         }))),
                 ),
@@ -50,9 +64,13 @@ pub fn HomeInputs(
                     .child(
                         tr().child(th().child("Output Value:"))
                             .child(th().child(move || {
-                                div().classes("value").child(
-                                    match base_conversion().output_string() {
-                                        Ok(v) => span().child(
+                                div().classes("value").child(match output() {
+                                    Ok(Output {
+                                        string: v,
+                                        error_bound,
+                                        ..
+                                    }) => span()
+                                        .child(
                                             code()
                                                 .child(move || {
                                                     show_accurate()
@@ -71,15 +89,22 @@ pub fn HomeInputs(
                                                 })
                                                 .child(span().inner_html("&nbsp"))
                                                 .child(sub().child(move || {
-                                                    rounded_bignum(
-                                                        base_conversion().output_base,
-                                                        None,
-                                                    )
+                                                    rounded_base(base_conversion().output_base)
                                                 })),
+                                        )
+                                        .child(
+                                            error_bound.filter(|b| b != &BigDecimal::from(0)).map(
+                                                |b| {
+                                                    div()
+                                                        .classes("bound")
+                                                        .child(format!("Off by at most {b}"))
+                                                },
+                                            ),
                                         ),
-                                        Err(e) => span().child(e),
-                                    },
-                                )
+                                    // The input error is already shown above
+                                    Err(ConversionError::InvalidInput(_)) => span(),
+                                    Err(e) => span().child(e.to_string()),
+                                })
                             })),
                     ),
             )
@@ -125,6 +150,21 @@ pub fn HomeInputs(
                             ),
                         ),
                     )
+                    .child(
+                        tr().child(
+                            td().child(label().attr("for", "InputDigits").child("Input Digits")),
+                        )
+                        .child(
+                            td().child(
+                                input()
+                                    .id("InputDigits")
+                                    .attr("type", "text")
+                                    .attr("placeholder", "standard")
+                                    .attr("value", move || base_conversion().input_digit_set_string)
+                                    .on(ev::input, update_base(set_input_digit_set_string)),
+                            ),
+                        ),
+                    )
                     .child(
                         tr().child(
                             td().child(label().attr("for", "OutputBase").child("Output Base")),
@@ -138,11 +178,127 @@ pub fn HomeInputs(
                                     .on(ev::input, update_base(set_output_base_string)),
                             ),
                         ),
+                    )
+                    .child(
+                        tr().child(
+                            td().child(label().attr("for", "OutputDigits").child("Output Digits")),
+                        )
+                        .child(
+                            td().child(
+                                input()
+                                    .id("OutputDigits")
+                                    .attr("type", "text")
+                                    .attr("placeholder", "standard")
+                                    .attr("value", move || {
+                                        base_conversion().output_digit_set_string
+                                    })
+                                    .on(ev::input, update_base(set_output_digit_set_string)),
+                            ),
+                        ),
+                    )
+                    .child(
+                        tr().child(
+                            td().child(label().attr("for", "ExponentBase").child("Exponent Base")),
+                        )
+                        .child(
+                            td().child(
+                                input()
+                                    .id("ExponentBase")
+                                    .attr("type", "text")
+                                    .attr("placeholder", "none")
+                                    .attr("value", move || base_conversion().exponent_base_string)
+                                    .on(ev::input, update_base(set_exponent_base_string)),
+                            ),
+                        ),
+                    )
+                    .child(
+                        tr().child(
+                            td().child(label().attr("for", "Permissive").child("Carry Digits")),
+                        )
+                        .child(
+                            td().child(
+                                input()
+                                    .id("Permissive")
+                                    .attr("type", "checkbox")
+                                    .prop("checked", move || base_conversion().permissive)
+                                    .on(ev::change, move |ev| {
+                                        set_permissive(event_target_checked(&ev))
+                                    }),
+                            )
+                            .child(" past the end of the input base"),
+                        ),
+                    )
+                    .child(
+                        tr().child(td().child(label().attr("for", "Precision").child("Precision")))
+                            .child(
+                                td().child(
+                                    input()
+                                        .id("Precision")
+                                        .attr("type", "number")
+                                        .attr("min", 0)
+                                        .attr("max", MAX_PRECISION)
+                                        .attr("value", move || base_conversion().precision)
+                                        .on(ev::input, move |ev| {
+                                            if let Ok(precision) = event_target_value(&ev).parse() {
+                                                set_precision(precision)
+                                            }
+                                        }),
+                                )
+                                .child(" digits"),
+                            ),
+                    )
+                    .child(
+                        tr().child(td().child(label().attr("for", "Rounding").child("Rounding")))
+                            .child(
+                                td().child(
+                                    select()
+                                        .id("Rounding")
+                                        .on(ev::change, move |ev| {
+                                            if let Ok(rounding) =
+                                                Rounding::from_str(&event_target_value(&ev))
+                                            {
+                                                set_rounding(rounding)
+                                            }
+                                        })
+                                        .child(
+                                            [
+                                                Rounding::Truncate,
+                                                Rounding::HalfEven,
+                                                Rounding::HalfUp,
+                                            ]
+                                            .map(
+                                                |rounding| {
+                                                    option()
+                                                        .attr("value", rounding.to_string())
+                                                        .prop("selected", move || {
+                                                            base_conversion().rounding == rounding
+                                                        })
+                                                        .child(rounding.to_string())
+                                                },
+                                            ),
+                                        ),
+                                ),
+                            ),
                     ),
             )
     }
 }
 
+/// Explain why the input can't be read, pointing out the offending character
+/// when there is one.
+fn input_error(input: &str, error: ConversionError) -> HtmlElement<Span> {
+    let chars: Vec<char> = input.chars().collect();
+    let highlight = error.position().filter(|&p| p < chars.len()).map(|p| {
+        span().child(": ").child(
+            code()
+                .child(chars[..p].iter().collect::<String>())
+                .child(mark().child(chars[p].to_string()))
+                .child(chars[p + 1..].iter().collect::<String>()),
+        )
+    });
+    span().child(error.to_string()).child(highlight)
+}
+
 fn update_base<SF>(setter: SF) -> impl Fn(Event)
 where
     SF: Fn(String),
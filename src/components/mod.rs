@@ -1,5 +1,7 @@
 pub mod counter_btn;
+pub mod digit_search;
 pub mod home_inputs;
+pub mod other_expansions;
 pub mod output_details;
 pub mod rounded_bignum;
 pub mod site_footer;
@@ -0,0 +1,65 @@
+use leptos::{html::*, *};
+
+use crate::bases::{BaseConversion, ConversionError, FiniteExpansions, Output};
+
+/// The most digits a listed representation can have, since every digit
+/// multiplies the representations to search through.
+static MAX_DIGITS: usize = 12;
+
+#[component]
+pub fn OtherExpansions(
+    base_conversion: Memo<BaseConversion>,
+    output: Memo<Result<Output, ConversionError>>,
+) -> impl IntoView {
+    let (is_open, set_is_open) = create_signal(false);
+
+    move || match (output(), is_open()) {
+        (Ok(_), _) if !base_conversion().output_is_positional() => div(),
+        (Ok(_), true) => div()
+            .child(
+                button()
+                    .classes("mx-auto")
+                    .on(ev::click, move |_| set_is_open(false))
+                    .child("Hide Other Representations"),
+            )
+            .child(match base_conversion().finite_expansions(MAX_DIGITS) {
+                Ok(FiniteExpansions {
+                    expansions,
+                    is_truncated: false,
+                }) if expansions.is_empty() => div().child(p().child(format!(
+                    "There are no finite representations with at most {MAX_DIGITS} digits."
+                ))),
+                Ok(FiniteExpansions {
+                    expansions,
+                    is_truncated,
+                }) => div()
+                    .child(p().child(format!(
+                        "Integer bases write each value just one way, but non-integer bases \
+                         can write the same value with different digits. These are the finite \
+                         representations with at most {MAX_DIGITS} digits, starting with the \
+                         usual greedy one:"
+                    )))
+                    .child(
+                        ul().child(
+                            expansions
+                                .into_iter()
+                                .map(|e| li().child(code().child(e)))
+                                .collect_view(),
+                        ),
+                    )
+                    .child(is_truncated.then(|| {
+                        p().child(
+                            "The search stopped before it was done, so there may be more \
+                             representations than these.",
+                        )
+                    })),
+                Err(e) => div().child(p().child(e.to_string())),
+            }),
+        (Ok(_), false) => div().child(
+            button()
+                .on(ev::click, move |_| set_is_open(true))
+                .child("Show Other Representations"),
+        ),
+        (Err(_), _) => div(),
+    }
+}
@@ -3,7 +3,7 @@ mod content;
 use content::content;
 use leptos::{html::*, *};
 
-use crate::bases::BaseConversion;
+use crate::bases::{BaseConversion, ConversionError, Output, UNCERTAIN};
 
 #[derive(Clone, Debug)]
 enum OpenState {
@@ -12,15 +12,27 @@ enum OpenState {
 }
 
 #[component]
-pub fn OutputDetails(base_conversion: Memo<BaseConversion>) -> impl IntoView {
+pub fn OutputDetails(
+    base_conversion: Memo<BaseConversion>,
+    output: Memo<Result<Output, ConversionError>>,
+) -> impl IntoView {
     let (is_open, set_is_open) = create_signal(OpenState::Closed);
     let close = move || set_is_open(OpenState::Closed);
     let open = move |_| set_is_open(OpenState::Open);
 
-    move || match (base_conversion().output_string(), is_open()) {
+    move || match (output().map(|o| o.numerals), is_open()) {
+        (Ok(_), _) if !base_conversion().output_is_positional() => div(),
+        // Uncertain digits don't have a value to show
+        (Ok(o), _) if o.contains(UNCERTAIN) => div(),
         (Ok(o), OpenState::Open) => div().child(content(
             create_memo(move |_| o.clone()),
             create_memo(move |_| base_conversion().output_base).into(),
+            create_memo(move |_| base_conversion().output_digit_set).into(),
+            base_conversion()
+                .input_normalization()
+                .ok()
+                .filter(|n| !n.is_admissible)
+                .map(|n| (base_conversion().input_base, n)),
             close,
         )),
         (Ok(_), OpenState::Closed) => {
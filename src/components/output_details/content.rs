@@ -4,8 +4,8 @@ use bigdecimal::BigDecimal;
 use leptos::{html::*, *};
 
 use crate::{
-    bases::{pow, rep_to_digit_exponent_pairs},
-    components::rounded_bignum::rounded_bignum,
+    bases::{split_sign, Base, Complex, DigitSet, Normalization, OVERBAR},
+    components::rounded_bignum::{rounded_base, rounded_bignum},
 };
 
 fn td_classes_generator() -> impl FnMut() -> String {
@@ -21,20 +21,100 @@ fn td_classes_generator() -> impl FnMut() -> String {
     }
 }
 
-pub fn content<G>(output: Memo<String>, base: Signal<BigDecimal>, close: G) -> impl IntoView
+/// Write a digit the way it would appear in a representation, bracketing
+/// digits that take more than one character.
+fn written_digit(c: &str) -> String {
+    match c.trim_end_matches(OVERBAR).chars().count() {
+        1 => c.to_string(),
+        _ => format!("[{c}]"),
+    }
+}
+
+/// The digits of a representation with their exponents, leaving out anything
+/// that isn't a digit, like the `…` of an elided output or the `EMPTY` of a
+/// bijective zero.
+fn digit_pairs(base: &Base, digit_set: &DigitSet, rep: &str) -> Vec<(String, isize)> {
+    base.digit_exponent_pairs(rep)
+        .into_iter()
+        .filter(|(c, _)| base.digit_value(c, digit_set).is_ok())
+        .collect()
+}
+
+/// Show the base so that a superscript exponent can follow it, wrapping
+/// negative and complex bases in parentheses.
+fn base_to_power(base: Complex) -> impl IntoView {
+    match !base.is_real() || base.re < BigDecimal::from(0) {
+        true => span()
+            .child('(')
+            .child(rounded_bignum(base, None))
+            .child(')'),
+        false => span().child(rounded_bignum(base, None)),
+    }
+}
+
+/// Show the place value of a position: a power of a positional base, or the
+/// evaluated weight of a mixed-radix or Fibonacci position.
+fn place_value(base: Base, exp: isize) -> View {
+    match base {
+        Base::Positional(base) => span()
+            .child(base_to_power(base))
+            .child(span().inner_html("&NoBreak;"))
+            .child(sup().child(exp))
+            .into_view(),
+        base => rounded_bignum(base.weight(exp), NonZeroU64::new(8)).into_view(),
+    }
+}
+
+/// Show how carrying digits rewrites an input that isn't the greedy expansion
+/// of its value in the input base.
+fn normalization_steps(input_base: Base, normalization: Normalization) -> impl IntoView {
+    div()
+        .child(
+            p().child("The input digits aren't the greedy expansion in base-")
+                .child(rounded_base(input_base))
+                .child(", which is ")
+                .child(code().child(normalization.canonical().to_string()))
+                .child(
+                    ". Carrying a 1 wherever the digits below a position add up to its place \
+                     value rewrites them:",
+                ),
+        )
+        .child(
+            p().child(
+                normalization
+                    .steps
+                    .into_iter()
+                    .map(|step| code().child(step).into_view())
+                    .intersperse_with(|| " → ".into_view())
+                    .collect_view(),
+            ),
+        )
+}
+
+pub fn content<G>(
+    output: Memo<String>,
+    base: Signal<Base>,
+    digit_set: Signal<DigitSet>,
+    normalization: Option<(Base, Normalization)>,
+    close: G,
+) -> impl IntoView
 where
     G: Fn() + 'static,
 {
-    let all_pairs = rep_to_digit_exponent_pairs(&output());
+    let all_pairs = digit_pairs(&base(), &digit_set(), &output());
+    let is_positional = matches!(base(), Base::Positional(_));
+    let is_negative = split_sign(&output()).0;
 
     let digit_exponent_pairs = create_memo(move |_| -> Vec<_> {
-        rep_to_digit_exponent_pairs(&output())
+        digit_pairs(&base(), &digit_set(), &output())
             .into_iter()
             .take(5)
             .collect()
     });
 
-    let needs_filler = all_pairs.len() > digit_exponent_pairs().len();
+    let needs_filler = all_pairs.len() > digit_exponent_pairs().len()
+        || output().contains('(')
+        || output().ends_with('…');
     let filler = move || match needs_filler {
         true => Some(
             td().child(span().classes("right-space").child("+"))
@@ -43,14 +123,18 @@ where
         false => None,
     };
 
-    let digit_to_value = |s: String| s.parse().or_else(|_| u32::from_str_radix(&s, 36)).unwrap();
+    let digit_to_value = move |s: String| base().digit_value(&s, &digit_set()).unwrap();
 
-    let digit_conversion = match base() > BigDecimal::from(10) {
+    let needs_digit_conversion = is_positional
+        && all_pairs
+            .iter()
+            .any(|(c, _)| digit_to_value(c.clone()).to_string() != *c);
+    let digit_conversion = match needs_digit_conversion {
         true => Some(
             tr().child(
                 td().classes("align-end")
                     .child("Represent base-")
-                    .child(move || rounded_bignum(base(), None))
+                    .child(move || rounded_base(base()))
                     .child(" digits as base-10 numbers:"),
             )
             .child(move || {
@@ -63,9 +147,7 @@ where
                         td().classes(gena())
                             .child(span().classes("highlight").child(digit_to_value(c)))
                             .child('(')
-                            .child(move || rounded_bignum(base(), None))
-                            .child(span().inner_html("&NoBreak;"))
-                            .child(sup().child(i))
+                            .child(move || place_value(base(), i))
                             .child(')')
                     })
                     .intersperse_with(|| td().classes(genb()).child("+"))
@@ -83,6 +165,7 @@ where
                 .on(ev::click, move |_| close())
                 .child("Hide Output Details"),
         )
+        .child(normalization.map(|(input_base, n)| normalization_steps(input_base, n)))
         .child(p().child("The output value can be converted to base-10:"))
         .child(
             table()
@@ -98,9 +181,12 @@ where
                     tbody()
                         .child(
                             tr().child(
-                                td().classes("align-end")
-                                    .child("Write the number in base-")
-                                    .child(move || rounded_bignum(base(), None)),
+                                td().classes("align-end").child(move || match base() {
+                                    Base::Positional(_) => "Write the number in base-",
+                                    Base::MixedRadix(_) => "Write the number in the radices ",
+                                    Base::Zeckendorf => "Write the number in ",
+                                })
+                                .child(move || rounded_base(base())),
                             )
                             .child(
                                 td().classes("highlight")
@@ -121,19 +207,20 @@ where
                                     .into_iter()
                                     .map(|(c, _)| {
                                         td().classes(gena()).attr("colspan", 2).child(
-                                            span().classes("highlight").child(match c.len() {
-                                                1 => c.to_string(),
-                                                _ => format!("[{c}]"),
-                                            }),
+                                            span().classes("highlight").child(written_digit(&c)),
                                         )
                                     })
                                     .collect_view()
                             }),
                         )
                         .child(
-                            tr().child(td().classes("align-end").child(
-                                "Multiply each digit by the base raised to the appropriate power",
-                            ))
+                            tr().child(td().classes("align-end").child(move || match base() {
+                                Base::Positional(_) => {
+                                    "Multiply each digit by the base raised to the appropriate power"
+                                }
+                                Base::MixedRadix(_) => "Multiply each digit by its place value",
+                                Base::Zeckendorf => "Multiply each digit by its Fibonacci weight",
+                            }))
                             .child(move || {
                                 let mut gena = td_classes_generator();
                                 let mut genb = td_classes_generator();
@@ -142,17 +229,12 @@ where
                                     .into_iter()
                                     .map(|(c, i)| {
                                         td().classes(gena())
-                                            .child(span().child(match c.len() {
-                                                1 => c.to_string(),
-                                                _ => format!("[{c}]"),
-                                            }))
+                                            .child(span().child(written_digit(&c)))
                                             .child('(')
                                             .child(
                                                 span()
                                                     .classes("highlight")
-                                                    .child(move || rounded_bignum(base(), None))
-                                                    .child(span().inner_html("&NoBreak;"))
-                                                    .child(sup().child(i)),
+                                                    .child(move || place_value(base(), i)),
                                             )
                                             .child(')')
                                     })
@@ -162,7 +244,7 @@ where
                             .child(filler()),
                         )
                         .child(digit_conversion)
-                        .child(
+                        .child(is_positional.then(|| {
                             tr().child(
                                 td().classes("align-end")
                                     .child("Evaluate the exponents on the base:"),
@@ -178,15 +260,15 @@ where
                                             .child(span().child(digit_to_value(c)))
                                             .child('(')
                                             .child(span().classes("highlight").child(move || {
-                                                rounded_bignum(pow(&base(), i), NonZeroU64::new(8))
+                                                rounded_bignum(base().weight(i), NonZeroU64::new(8))
                                             }))
                                             .child(')')
                                     })
                                     .intersperse_with(|| td().classes(genb()).child("+"))
                                     .collect_view()
                             })
-                            .child(filler()),
-                        )
+                            .child(filler())
+                        }))
                         .child(
                             tr().child(td().classes("align-end").child("Multiply to get:"))
                                 .child(move || {
@@ -198,7 +280,8 @@ where
                                         .map(|(c, i)| {
                                             td().classes(gena()).child(
                                                 span().classes("highlight").child(rounded_bignum(
-                                                    pow(&base(), i) * digit_to_value(c),
+                                                    base().weight(i)
+                                                        * BigDecimal::from(digit_to_value(c)),
                                                     NonZeroU64::new(8),
                                                 )),
                                             )
@@ -211,31 +294,74 @@ where
                 )
                 .child(
                     tfoot().child(
-                        tr().child(th().classes("align-end").child("Adding everything:"))
-                            .child(
-                                th().attr("align", "left")
-                                    .attr("colspan", move || digit_exponent_pairs().len() * 2)
-                                    .child(
-                                        span()
-                                            .classes("highlight")
-                                            .child(move || {
-                                                rounded_bignum(
-                                                    digit_exponent_pairs()
-                                                        .into_iter()
-                                                        .map(|(c, i)| {
-                                                            pow(&base(), i) * digit_to_value(c)
-                                                        })
-                                                        .sum::<BigDecimal>(),
-                                                    None,
-                                                )
-                                            })
-                                            .child(match needs_filler {
-                                                true => Some(" + …"), // ellide
-                                                false => None,
-                                            }),
-                                    ),
-                            ),
+                        tr().child(th().classes("align-end").child(match is_negative {
+                            true => "Adding everything and negating:",
+                            false => "Adding everything:",
+                        }))
+                        .child(
+                            th().attr("align", "left")
+                                .attr("colspan", move || digit_exponent_pairs().len() * 2)
+                                .child(
+                                    span()
+                                        .classes("highlight")
+                                        .child(move || {
+                                            let sum = digit_exponent_pairs()
+                                                .into_iter()
+                                                .map(|(c, i)| {
+                                                    base().weight(i)
+                                                        * BigDecimal::from(digit_to_value(c))
+                                                })
+                                                .sum::<Complex>();
+                                            rounded_bignum(
+                                                match is_negative {
+                                                    true => -sum,
+                                                    false => sum,
+                                                },
+                                                None,
+                                            )
+                                        })
+                                        .child(match needs_filler {
+                                            true => Some(" + …"), // ellide
+                                            false => None,
+                                        }),
+                                ),
+                        ),
                     ),
                 ),
         )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bases::BaseConversion;
+
+    fn details(conversion: BaseConversion) -> String {
+        leptos::ssr::render_to_string(move || {
+            let output = conversion.output().unwrap().numerals;
+            content(
+                create_memo(move |_| output.clone()),
+                Signal::derive(move || conversion.output_base.clone()),
+                Signal::derive(move || conversion.output_digit_set.clone()),
+                None,
+                || (),
+            )
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn leaves_out_what_isnt_a_digit() {
+        let pi = BaseConversion::for_test("123.45", "10", "pi");
+        assert!(pi.output().unwrap().numerals.ends_with('…'));
+        assert!(details(pi).contains("+ …"));
+
+        let zero = BaseConversion {
+            output_digit_set_string: "bijective".to_string(),
+            ..BaseConversion::for_test("0", "10", "10")
+        }
+        .reparsed();
+        assert_eq!("ε", zero.output().unwrap().numerals);
+        assert!(details(zero).contains("Adding everything:"));
+    }
+}
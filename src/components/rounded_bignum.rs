@@ -1,14 +1,22 @@
-use bigdecimal::BigDecimal;
 use leptos::{html::*, *};
 use std::num::NonZeroU64;
 
-use crate::bases::rounded_string;
+use crate::bases::{rounded_complex_string, Base, Complex};
 
-pub fn rounded_bignum(num: BigDecimal, hard_limit: Option<NonZeroU64>) -> impl IntoView {
-    let rounded = rounded_string(&num, hard_limit);
+pub fn rounded_bignum(num: impl Into<Complex>, hard_limit: Option<NonZeroU64>) -> impl IntoView {
+    let num = num.into();
+    let rounded = rounded_complex_string(&num, hard_limit);
     let s = span().child(&rounded);
     if rounded != num.to_string() {
         return s.attr("tabindex", "0").attr("title", num.to_string());
     }
     s
 }
+
+/// Show a base the way it's named in the page, rounding positional bases.
+pub fn rounded_base(base: Base) -> View {
+    match base {
+        Base::Positional(base) => rounded_bignum(base, None).into_view(),
+        base => span().child(base.to_string()).into_view(),
+    }
+}
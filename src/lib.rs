@@ -1,4 +1,5 @@
 #![feature(iter_intersperse)]
+#![cfg_attr(test, feature(test))]
 
 use leptos::*;
 use leptos_meta::*;
@@ -1,9 +1,10 @@
 use leptos::{html::*, *};
 
 use crate::{
-    bases::BaseConversion,
+    bases::{BaseConversion, Rounding},
     components::{
-        home_inputs::HomeInputs, output_details::OutputDetails, site_footer::site_footer,
+        digit_search::DigitSearch, home_inputs::HomeInputs, other_expansions::OtherExpansions,
+        output_details::OutputDetails, site_footer::site_footer,
     },
 };
 
@@ -12,17 +13,34 @@ use crate::{
 pub fn Home() -> impl IntoView {
     let (input_string, set_input_string) = create_signal("123.45".to_string());
     let (input_base_string, set_input_base_string) = create_signal(String::from("10"));
+    let (input_digit_set_string, set_input_digit_set_string) =
+        create_signal(String::from("standard"));
     let (output_base_string, set_output_base_string) = create_signal(String::from("π"));
+    let (output_digit_set_string, set_output_digit_set_string) =
+        create_signal(String::from("standard"));
+    let (precision, set_precision) = create_signal(8);
+    let (exponent_base_string, set_exponent_base_string) = create_signal(String::new());
+    let (permissive, set_permissive) = create_signal(false);
+    let (rounding, set_rounding) = create_signal(Rounding::Truncate);
 
     let base_conversion = create_memo::<BaseConversion>(move |prev| {
         BaseConversion::new_with_defaults(
             input_string(),
             input_base_string(),
+            input_digit_set_string(),
             output_base_string(),
+            output_digit_set_string(),
+            precision(),
+            exponent_base_string(),
+            permissive(),
+            rounding(),
             prev,
         )
     });
 
+    // Writing the output can take a while, so it's only done once per change
+    let output = create_memo(move |_| base_conversion().output());
+
     let (accurate_conversion, set_accurate_conversion) = create_signal::<Option<String>>(None);
 
     create_effect(move |_| {
@@ -39,6 +57,43 @@ pub fn Home() -> impl IntoView {
         .child(code().child("sqrt2"))
         .child(", ")
         .child(code().child("phi"))
+        .child(", ")
+        .child(code().child("sqrt(7)"))
+        .child(", ")
+        .child(code().child("negabinary"))
+        .child(", ")
+        .child(code().child("2i"))
+        .child(", ")
+        .child(code().child("-1+i"))
+        .child(", ")
+        .child(code().child("time"))
+        .child(", ")
+        .child(code().child("factorial"))
+        .child(", ")
+        .child(code().child("zeckendorf"))
+        .child(".");
+
+    let also_try_digits = sub()
+        .child("Also try digits: ")
+        .child(code().child("balanced"))
+        .child(", ")
+        .child(code().child("naf"))
+        .child(", ")
+        .child(code().child("lazy"))
+        .child(", ")
+        .child(code().child("quasi-greedy"))
+        .child(", ")
+        .child(code().child("{-1,0,1,2}"))
+        .child(", ")
+        .child(code().child("bijective"))
+        .child(", ")
+        .child(code().child("spreadsheet"))
+        .child(", ")
+        .child(code().child("lowercase"))
+        .child(", ")
+        .child(code().child("base62"))
+        .child(", ")
+        .child(code().child("balanced \"ABCDEFGHIJ\""))
         .child(".");
 
     let footer = site_footer();
@@ -69,16 +124,29 @@ pub fn Home() -> impl IntoView {
 
                 <HomeInputs
                     base_conversion=base_conversion
+                    output=output
                     set_input_string=set_input_string
                     set_input_base_string=set_input_base_string
+                    set_input_digit_set_string=set_input_digit_set_string
                     set_output_base_string=set_output_base_string
+                    set_output_digit_set_string=set_output_digit_set_string
+                    set_precision=set_precision
+                    set_exponent_base_string=set_exponent_base_string
+                    set_permissive=set_permissive
+                    set_rounding=set_rounding
                     accurate_conversion=accurate_conversion
                     set_accurate_conversion=set_accurate_conversion
                 />
 
                 {also_try}
 
-                <OutputDetails base_conversion=base_conversion />
+                {also_try_digits}
+
+                <OutputDetails base_conversion=base_conversion output=output />
+
+                <OtherExpansions base_conversion=base_conversion output=output />
+
+                <DigitSearch base_conversion=base_conversion />
 
                 {footer}
 